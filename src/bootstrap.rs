@@ -1,16 +1,16 @@
-use soroban_sdk::{contracttype, Env};
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Env, Symbol, Vec};
 
 use crate::{
-    constants::MAX_DUST_AMOUNT,
-    storage::{self, ONE_DAY_LEDGERS},
-    types::{BootstrapConfig, BootstrapData, BootstrapStatus},
+    constants::SCALAR_7,
+    storage,
+    types::{BootstrapConfig, BootstrapData, BootstrapStatus, DepositData, DustData},
 };
 
 #[derive(Clone)]
 #[contracttype]
 pub struct Bootstrap {
     pub id: u32,
-    pub status: BootstrapStatus,
     pub config: BootstrapConfig,
     pub data: BootstrapData,
 }
@@ -23,29 +23,68 @@ impl Bootstrap {
     pub fn load(e: &Env, id: u32) -> Self {
         let config = storage::get_bootstrap_config(e, id);
         let data = storage::get_bootstrap_data(e, id);
-        let status: BootstrapStatus;
-        if e.ledger().sequence() < config.close_ledger {
-            status = BootstrapStatus::Active;
-        } else if data.total_pair < config.pair_min {
-            status = BootstrapStatus::Cancelled;
-        } else if data.pair_amount <= MAX_DUST_AMOUNT
-            && data.bootstrap_amount <= MAX_DUST_AMOUNT
-            && data.total_backstop_tokens >= MAX_DUST_AMOUNT
+        Bootstrap { id, config, data }
+    }
+
+    /// Load a bootstrap from storage with its current status, without storing any transitions
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    pub fn load_current(e: &Env, id: u32) -> Self {
+        let mut bootstrap = Bootstrap::load(e, id);
+        bootstrap.data.status = bootstrap.current_status(e);
+        bootstrap
+    }
+
+    /// Get the status the bootstrap is in at the current ledger, including any time-based
+    /// transitions that have not been stored yet
+    pub fn current_status(&self, e: &Env) -> BootstrapStatus {
+        let mut status = self.data.status;
+        if status == BootstrapStatus::Active && e.ledger().sequence() >= self.config.close_ledger {
+            status = if !self.pair_min_met() {
+                BootstrapStatus::Cancelled
+            } else {
+                BootstrapStatus::Closing
+            };
+        }
+        // an expired bootstrap keeps whatever it has already converted
+        if status == BootstrapStatus::Closing
+            && self.config.close_ledger + self.data.close_expiry < e.ledger().sequence()
         {
-            status = BootstrapStatus::Completed;
-        } else if config.close_ledger + 14 * ONE_DAY_LEDGERS < e.ledger().sequence() {
-            status = BootstrapStatus::Cancelled;
-        } else {
-            status = BootstrapStatus::Closing;
+            status = if self.data.total_backstop_tokens > self.data.backstop_token_deposits {
+                BootstrapStatus::PartiallyCompleted
+            } else {
+                BootstrapStatus::Cancelled
+            };
         }
-        Bootstrap {
-            id,
-            status,
-            config,
-            data,
+        status
+    }
+
+    /// Apply any time-based status transitions that have not been stored yet. The bootstrap
+    /// must be stored for the transition to persist.
+    ///
+    /// Returns true if the status changed
+    pub fn update_status(&mut self, e: &Env) -> bool {
+        let status = self.current_status(e);
+        if status != self.data.status {
+            self.set_status(e, status);
+            true
+        } else {
+            false
         }
     }
 
+    /// Transition the bootstrap to a new status. The bootstrap must be stored for the
+    /// transition to persist.
+    ///
+    /// ### Arguments
+    /// * `status` - The new status of the bootstrap
+    pub fn set_status(&mut self, e: &Env, status: BootstrapStatus) {
+        self.data.status = status;
+        e.events()
+            .publish((Symbol::new(e, "bootstrap_status"), self.id), status);
+    }
+
     /// Store the bootstrap data to storage
     pub fn store(&self, e: &Env) {
         storage::set_bootstrap_data(e, self.id, &self.data);
@@ -54,34 +93,312 @@ impl Bootstrap {
     /// Join the bootstrap
     ///
     /// ### Arguments
-    /// * `amount` - The amount of the pair token to join with
-    pub fn join(&mut self, amount: i128) {
-        self.data.pair_amount += amount;
-        self.data.total_pair += amount;
+    /// * `index` - The comet token index of the token to join with
+    /// * `amount` - The amount of the token to join with
+    pub fn join(&mut self, index: u32, amount: i128) {
+        self.add_pair(index, amount);
     }
 
     /// Exit the bootstrap
     ///
     /// ### Arguments
-    /// * `amount` - The amount of the pair token to exit with
-    pub fn exit(&mut self, amount: i128) {
-        self.data.pair_amount -= amount;
-        self.data.total_pair -= amount;
+    /// * `index` - The comet token index of the token to exit with
+    /// * `amount` - The amount of the token to exit with
+    pub fn exit(&mut self, index: u32, amount: i128) {
+        self.add_pair(index, -amount);
+    }
+
+    /// Get the amount of a comet token held by the contract for the bootstrap
+    ///
+    /// ### Arguments
+    /// * `index` - The comet token index
+    pub fn amount_held(&self, index: u32) -> i128 {
+        if index == self.config.token_index {
+            self.data.bootstrap_amount + self.data.pair_amounts.get_unchecked(index)
+        } else {
+            self.data.pair_amounts.get_unchecked(index)
+        }
+    }
+
+    /// Get the amount of a comet token the contract still holds for the bootstrap, after any
+    /// refunds and sweeps
+    ///
+    /// ### Arguments
+    /// * `index` - The comet token index
+    pub fn amount_unpaid(&self, index: u32) -> i128 {
+        self.amount_held(index) - self.data.paid_amounts.get_unchecked(index)
+    }
+
+    /// Get the backstop tokens the contract still holds for the bootstrap, including any that
+    /// are unclaimed or still vesting
+    pub fn backstop_tokens_unpaid(&self) -> i128 {
+        self.data.total_backstop_tokens - self.data.paid_backstop_tokens
+    }
+
+    /// Get the leftover tokens held for the bootstrap that are owed to no one. The unconverted
+    /// tokens of a completed bootstrap are never paid out, so they are dust as soon as it
+    /// completes. Anything else left behind by rounding is only dust once the bootstrapper and
+    /// every depositor have claimed or been refunded.
+    pub fn dust(&self, e: &Env) -> DustData {
+        let status = self.data.status;
+        let settled = self.data.depositors == 0
+            && match status {
+                BootstrapStatus::Completed | BootstrapStatus::PartiallyCompleted => {
+                    storage::get_claimed(e, self.id)
+                }
+                BootstrapStatus::Cancelled => storage::get_refunded(e, self.id),
+                _ => false,
+            };
+        let mut amounts: Vec<i128> = Vec::new(e);
+        for index in 0..self.data.pair_amounts.len() {
+            amounts.push_back(if status == BootstrapStatus::Completed || settled {
+                self.amount_unpaid(index)
+            } else {
+                0
+            });
+        }
+        let backstop_tokens = if settled {
+            self.data.total_backstop_tokens - self.data.claimed_backstop_tokens
+        } else {
+            0
+        };
+        DustData {
+            amounts,
+            backstop_tokens,
+        }
     }
 
-    /// Spend bootstrap and pair tokens to mint backstop tokens
+    /// Get the amount of a comet token to deposit single sided on close. The bootstrap token is
+    /// only deposited if more than dust remains.
     ///
     /// ### Arguments
-    /// * `bootstrap_amount` - The amount of the bootstrap token to spend
-    /// * `pair_amount` - The amount of the pair token to spend
-    /// * `backstop_tokens` - The amount of backstop tokens to mint
-    pub fn convert(&mut self, bootstrap_amount: i128, pair_amount: i128, backstop_tokens: i128) {
-        if bootstrap_amount > 0 {
-            self.data.bootstrap_amount -= bootstrap_amount;
+    /// * `index` - The comet token index
+    pub fn single_sided_amount(&self, index: u32) -> i128 {
+        let amount = self.amount_held(index);
+        let min_amount = if index == self.config.token_index {
+            self.data.max_dust_amount
+        } else {
+            0
+        };
+        if amount > min_amount {
+            amount
+        } else {
+            0
         }
-        if pair_amount > 0 {
-            self.data.pair_amount -= pair_amount;
+    }
+
+    /// Get the comet token indexes in the order they are deposited single sided on close,
+    /// starting with the bootstrap token
+    pub fn close_order(&self) -> Vec<u32> {
+        let mut order = Vec::new(self.data.weights.env());
+        order.push_back(self.config.token_index);
+        for index in 0..self.data.weights.len() {
+            if index != self.config.token_index {
+                order.push_back(index);
+            }
         }
+        order
+    }
+
+    /// Get the comet token index the bootstrap token's price is quoted in. This is the first
+    /// comet token that is not the bootstrap token.
+    pub fn price_index(&self) -> u32 {
+        if self.config.token_index == 0 {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn add_pair(&mut self, index: u32, amount: i128) {
+        let pair_amount = self.data.pair_amounts.get_unchecked(index);
+        self.data.pair_amounts.set(index, pair_amount + amount);
+        let pair_deposit = self.data.pair_deposits.get_unchecked(index);
+        self.data.pair_deposits.set(index, pair_deposit + amount);
+    }
+
+    /// Check if the amount deposited of each pair token meets its minimum
+    pub fn pair_min_met(&self) -> bool {
+        self.config
+            .pair_min
+            .iter()
+            .enumerate()
+            .all(|(index, min)| self.data.pair_deposits.get_unchecked(index as u32) >= min)
+    }
+
+    /// Get the backstop tokens owed to a depositor. The backstop tokens minted by each comet
+    /// token are split across its depositors, and deposited backstop tokens are owed as-is.
+    ///
+    /// ### Arguments
+    /// * `deposit` - The deposit of the user
+    pub fn claimable(&self, deposit: &DepositData) -> i128 {
+        let mut owed = deposit.backstop_tokens;
+        for (index, amount) in deposit.amounts.iter().enumerate() {
+            if amount > 0 {
+                let index = index as u32;
+                owed += amount
+                    .fixed_div_floor(self.data.pair_deposits.get_unchecked(index), SCALAR_7)
+                    .unwrap_optimized()
+                    .fixed_mul_floor(
+                        self.data.pair_backstop_tokens.get_unchecked(index),
+                        SCALAR_7,
+                    )
+                    .unwrap_optimized();
+            }
+        }
+        owed
+    }
+
+    /// Get the amount of each comet token refundable to a depositor, indexed by comet token
+    /// index. Deposited backstop tokens are refunded as-is.
+    ///
+    /// ### Arguments
+    /// * `deposit` - The deposit of the user
+    pub fn refundable(&self, e: &Env, deposit: &DepositData) -> Vec<i128> {
+        let mut refundable: Vec<i128> = Vec::new(e);
+        for (index, amount) in deposit.amounts.iter().enumerate() {
+            let index = index as u32;
+            if amount <= 0 {
+                refundable.push_back(0);
+                continue;
+            }
+            refundable.push_back(
+                amount
+                    .fixed_mul_floor(
+                        self.data
+                            .pair_amounts
+                            .get_unchecked(index)
+                            .fixed_div_floor(self.data.pair_deposits.get_unchecked(index), SCALAR_7)
+                            .unwrap_optimized(),
+                        SCALAR_7,
+                    )
+                    .unwrap_optimized(),
+            );
+        }
+        refundable
+    }
+
+    /// Get the amount of each comet token refundable to the bootstrapper, indexed by comet token
+    /// index
+    pub fn bootstrapper_refundable(&self, e: &Env) -> Vec<i128> {
+        let mut refundable: Vec<i128> = Vec::new(e);
+        for index in 0..self.data.pair_amounts.len() {
+            refundable.push_back(if index == self.config.token_index {
+                self.data.bootstrap_amount
+            } else {
+                0
+            });
+        }
+        refundable
+    }
+
+    /// Record the current comet spot price of the bootstrap token into the time-weighted average.
+    /// If the bootstrap has a maximum price deviation, the recorded price is clamped to within it
+    /// of the current average, so a single skewed sample can't drag the average away.
+    ///
+    /// ### Arguments
+    /// * `price` - The current comet spot price of the bootstrap token, in pair tokens
+    pub fn record_price(&mut self, e: &Env, price: i128) {
+        let mut price = price;
+        if self.config.max_price_deviation > 0 {
+            let twap = self.twap(e);
+            let max_delta = twap
+                .fixed_mul_floor(self.config.max_price_deviation, SCALAR_7)
+                .unwrap_optimized();
+            price = price.clamp(twap - max_delta, twap + max_delta);
+        }
+        let ledger = e.ledger().sequence();
+        self.data.price_cumulative +=
+            self.data.last_price * (ledger - self.data.last_price_ledger) as i128;
+        self.data.last_price = price;
+        self.data.last_price_ledger = ledger;
+    }
+
+    /// Get the time-weighted average comet spot price of the bootstrap token, in pair tokens
+    pub fn twap(&self, e: &Env) -> i128 {
+        let ledger = e.ledger().sequence();
+        let elapsed = ledger - self.data.price_start_ledger;
+        if elapsed == 0 {
+            return self.data.last_price;
+        }
+        let cumulative = self.data.price_cumulative
+            + self.data.last_price * (ledger - self.data.last_price_ledger) as i128;
+        cumulative / elapsed as i128
+    }
+
+    /// Spend a single comet token to mint backstop tokens
+    ///
+    /// ### Arguments
+    /// * `index` - The comet token index of the token spent
+    /// * `amount` - The amount of the token spent
+    /// * `backstop_tokens` - The amount of backstop tokens minted
+    pub fn convert_single(&mut self, index: u32, amount: i128, backstop_tokens: i128) {
+        let mut amounts = Vec::new(self.data.weights.env());
+        for i in 0..self.data.weights.len() {
+            amounts.push_back(if i == index { amount } else { 0 });
+        }
+        self.convert(&amounts, backstop_tokens);
+    }
+
+    /// Spend comet tokens to mint backstop tokens. The keeper fee is taken from the minted
+    /// backstop tokens, and the rest are attributed to the tokens that were spent. If multiple
+    /// tokens are spent, they were deposited at the comet ratio, so the backstop tokens are split
+    /// by the snapshotted comet weights of the spent tokens.
+    ///
+    /// ### Arguments
+    /// * `amounts` - The amount of each comet token spent, indexed by comet token index
+    /// * `backstop_tokens` - The amount of backstop tokens minted
+    pub fn convert(&mut self, amounts: &Vec<i128>, backstop_tokens: i128) {
+        let keeper_fee = backstop_tokens
+            .fixed_mul_floor(self.config.keeper_fee, SCALAR_7)
+            .unwrap_optimized();
+        self.data.keeper_fees += keeper_fee;
+        let backstop_tokens = backstop_tokens - keeper_fee;
         self.data.total_backstop_tokens += backstop_tokens;
+
+        let mut spent_weight = 0;
+        let mut last_spent = 0;
+        for (index, amount) in amounts.iter().enumerate() {
+            if amount > 0 {
+                spent_weight += self.data.weights.get_unchecked(index as u32);
+                last_spent = index as u32;
+            }
+        }
+        let mut remaining = backstop_tokens;
+        for (index, amount) in amounts.iter().enumerate() {
+            let index = index as u32;
+            if amount <= 0 {
+                continue;
+            }
+            // the last spent token receives the rounding remainder
+            let token_backstop_tokens = if index == last_spent {
+                remaining
+            } else {
+                backstop_tokens
+                    .fixed_mul_floor(self.data.weights.get_unchecked(index), spent_weight)
+                    .unwrap_optimized()
+            };
+            remaining -= token_backstop_tokens;
+            let pair_amount = self.data.pair_amounts.get_unchecked(index);
+            let (pair_spent, pair_backstop_tokens) = if index == self.config.token_index {
+                // depositors of the bootstrap token share its backstop tokens with the
+                // bootstrapper by the amount each side has contributed
+                let held = self.data.bootstrap_amount + pair_amount;
+                let pair_spent = amount.fixed_mul_floor(pair_amount, held).unwrap_optimized();
+                let pair_backstop_tokens = token_backstop_tokens
+                    .fixed_mul_floor(pair_amount, held)
+                    .unwrap_optimized();
+                self.data.bootstrap_amount -= amount - pair_spent;
+                self.data.bootstrap_backstop_tokens += token_backstop_tokens - pair_backstop_tokens;
+                (pair_spent, pair_backstop_tokens)
+            } else {
+                (amount, token_backstop_tokens)
+            };
+            self.data.pair_amounts.set(index, pair_amount - pair_spent);
+            let total_pair_backstop_tokens = self.data.pair_backstop_tokens.get_unchecked(index);
+            self.data
+                .pair_backstop_tokens
+                .set(index, total_pair_backstop_tokens + pair_backstop_tokens);
+        }
     }
 }
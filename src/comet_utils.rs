@@ -1,5 +1,6 @@
 use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{
+    assert_with_error,
     auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
     token::TokenClient,
     unwrap::UnwrapOptimized,
@@ -8,129 +9,210 @@ use soroban_sdk::{
 
 use crate::{
     bootstrap::Bootstrap,
-    constants::{MAX_IN_RATIO, SCALAR_7},
+    constants::SCALAR_7,
     dependencies::comet,
+    errors::BackstopBootstrapperError,
     storage,
-    types::TokenInfo,
+    types::{CloseQuote, ProtocolParams, TokenInfo},
 };
 
-/// Execute join pool against comet
+/// The maximum number of close calls `simulate_close` simulates
+const MAX_SIMULATED_CLOSES: u32 = 10;
+/// 1 with 18 decimal places, used for comet's power approximation
+const SCALAR_18: i128 = 1_000_000_000_000_000_000;
+/// The precision at which comet's power approximation stops (18 decimals)
+const POW_PRECISION: i128 = 100_000_000;
+
+/// Fetch the underlying tokens and their normalized weights from comet
+///
+/// ### Arguments
+/// * `e` - The environment
+/// * `comet_client` - The comet client
+pub fn get_token_data(e: &Env, comet_client: &comet::Client) -> Vec<TokenInfo> {
+    let tokens = comet_client.get_tokens();
+    let mut token_data: Vec<TokenInfo> = Vec::new(e);
+    for address in tokens.iter() {
+        let weight = comet_client.get_normalized_weight(&address);
+        token_data.push_back(TokenInfo { address, weight });
+    }
+    token_data
+}
+
+/// Refresh the cached comet token data. Emits an event if the underlying tokens or
+/// weights changed since the last sync.
 ///
-/// Returns (amount of bootstrap deposited, amount of pair tokens deposited, amount of shares minted)
+/// Returns the current comet token data
 ///
 /// ### Arguments
 /// * `e` - The environment
+pub fn sync_token_data(e: &Env) -> Vec<TokenInfo> {
+    let comet_client = comet::Client::new(e, &storage::get_backstop_token(e));
+    let token_data = get_token_data(e, &comet_client);
+    if token_data != storage::get_comet_token_data(e) {
+        storage::set_comet_token_data(e, &token_data);
+        e.events()
+            .publish((Symbol::new(e, "token_data"),), token_data.clone());
+    }
+    token_data
+}
+
+/// Fetch the comet spot price of the bootstrap token, in pair tokens
+///
+/// ### Arguments
 /// * `comet_client` - The comet client
-/// * `tokens` - The comet tokens
-/// * `bootstrap` - The bootstrap (modified in place)
-/// * `bootstrap_bal` - The current contract balance of bootstrap tokens
-/// * `pair_bal` - The current contract balance of pair tokens
-/// * `comet_bootstrap_bal` - The current contract balance of comet bootstrap tokens (modified in place)
-/// * `comet_pair_bal` - The current contract balance of comet pair tokens (modified in place)
-/// * `comet_shares` - The current contract balance of comet shares (modified in place)
-pub fn join_pool(
-    e: &Env,
+/// * `bootstrap_token` - The address of the bootstrap token
+/// * `pair_token` - The address of the pair token
+pub fn get_spot_price(
     comet_client: &comet::Client,
-    tokens: &Vec<TokenInfo>,
+    bootstrap_token: &Address,
+    pair_token: &Address,
+) -> i128 {
+    comet_client.get_spot_price_sans_fee(pair_token, bootstrap_token)
+}
+
+/// Calculate the comet shares to mint with a join pool of every comet token held for the
+/// bootstrap
+///
+/// ### Arguments
+/// * `bootstrap` - The bootstrap
+/// * `comet_bals` - The current comet balance of each comet token
+/// * `comet_shares` - The current total supply of comet shares
+/// * `params` - The protocol parameters
+pub fn join_pool_shares(
     bootstrap: &Bootstrap,
-    bootstrap_bal: i128,
-    pair_bal: i128,
-    comet_bootstrap_bal: i128,
-    comet_pair_bal: i128,
+    comet_bals: &Vec<i128>,
     comet_shares: i128,
-) -> (i128, i128, i128) {
-    let bootstrap_info = tokens.get_unchecked(bootstrap.config.token_index);
-    let pair_info = tokens.get_unchecked(bootstrap.config.token_index ^ 1);
-
-    // underlying per LP token
-    let expected_tokens = bootstrap
-        .data
-        .bootstrap_amount
-        .fixed_div_floor(comet_bootstrap_bal, SCALAR_7)
-        .unwrap_optimized()
-        .fixed_mul_floor(comet_shares, SCALAR_7)
-        .unwrap_optimized()
-        .min(
+    params: &ProtocolParams,
+) -> i128 {
+    // the shares mintable are limited by the token held in the lowest ratio to the comet balance
+    let mut shares = i128::MAX;
+    for index in 0..comet_bals.len() {
+        shares = shares.min(
             bootstrap
-                .data
-                .pair_amount
-                .fixed_div_floor(comet_pair_bal, SCALAR_7)
+                .amount_held(index)
+                .fixed_div_floor(comet_bals.get_unchecked(index), SCALAR_7)
                 .unwrap_optimized()
                 .fixed_mul_floor(comet_shares, SCALAR_7)
                 .unwrap_optimized(),
-        )
-        .fixed_mul_floor(0_9999000, SCALAR_7) // we want to leave a little bit of room for rounding
-        .unwrap_optimized();
+        );
+    }
+    // we want to leave a little bit of room for rounding
+    shares
+        .fixed_mul_floor(params.join_buffer, SCALAR_7)
+        .unwrap_optimized()
+}
+
+/// Execute join pool against comet with every comet token held for the bootstrap. A join pool
+/// deposits every token at the comet ratio, so it has no price impact. It can still overpay if
+/// comet is skewed, which is caught by the bootstrap's price deviation check before any deposit
+/// is made. The price deviation check is required whenever `max_slippage` is set.
+///
+/// Returns (amount of each comet token deposited, amount of shares minted)
+///
+/// ### Arguments
+/// * `e` - The environment
+/// * `comet_client` - The comet client
+/// * `tokens` - The comet tokens
+/// * `bootstrap` - The bootstrap
+/// * `comet_bals` - The current comet balance of each comet token
+/// * `comet_shares` - The current total supply of comet shares
+/// * `params` - The protocol parameters
+pub fn join_pool(
+    e: &Env,
+    comet_client: &comet::Client,
+    tokens: &Vec<TokenInfo>,
+    bootstrap: &Bootstrap,
+    comet_bals: &Vec<i128>,
+    comet_shares: i128,
+    params: &ProtocolParams,
+) -> (Vec<i128>, i128) {
+    let expected_tokens = join_pool_shares(bootstrap, comet_bals, comet_shares, params);
+
+    let mut deposited: Vec<i128> = Vec::new(e);
+    if expected_tokens <= 0 {
+        return (deposited, 0);
+    }
 
     // handle join_pool
     let approval_ledger = (e.ledger().sequence() / 100000 + 1) * 100000;
-    if expected_tokens > 0 {
-        let mut auths = vec![&e];
-        let mut amounts_in = vec![&e];
-        for index in 0..2 {
-            let (address, amount) = if index == bootstrap.config.token_index {
-                amounts_in.push_back(bootstrap.data.bootstrap_amount);
-                (
-                    bootstrap_info.address.clone(),
-                    bootstrap.data.bootstrap_amount,
-                )
-            } else {
-                amounts_in.push_back(bootstrap.data.pair_amount);
-                (pair_info.address.clone(), bootstrap.data.pair_amount)
-            };
-            auths.push_back(InvokerContractAuthEntry::Contract(SubContractInvocation {
-                context: ContractContext {
-                    contract: address,
-                    fn_name: Symbol::new(&e, "approve"),
-                    args: vec![
-                        &e,
-                        e.current_contract_address().into_val(e),
-                        storage::get_backstop_token(&e).into_val(e),
-                        amount.into_val(e),
-                        approval_ledger.into_val(e),
-                    ],
-                },
-                sub_invocations: vec![e],
-            }));
-        }
-        e.authorize_as_current_contract(auths);
-        comet_client.join_pool(&expected_tokens, &amounts_in, &e.current_contract_address());
-
-        let deposited_bootstrap_tokens = bootstrap_bal
-            - TokenClient::new(e, &bootstrap_info.address).balance(&e.current_contract_address());
-        let deposited_pair_tokens = pair_bal
-            - TokenClient::new(e, &pair_info.address).balance(&e.current_contract_address());
-        (
-            deposited_bootstrap_tokens,
-            deposited_pair_tokens,
-            expected_tokens,
-        )
-    } else {
-        (0, 0, 0)
+    let mut auths = vec![&e];
+    let mut amounts_in = vec![&e];
+    let mut balances: Vec<i128> = Vec::new(e);
+    for (index, token) in tokens.iter().enumerate() {
+        let amount = bootstrap.amount_held(index as u32);
+        amounts_in.push_back(amount);
+        balances
+            .push_back(TokenClient::new(e, &token.address).balance(&e.current_contract_address()));
+        auths.push_back(InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token.address,
+                fn_name: Symbol::new(&e, "approve"),
+                args: vec![
+                    &e,
+                    e.current_contract_address().into_val(e),
+                    storage::get_backstop_token(&e).into_val(e),
+                    amount.into_val(e),
+                    approval_ledger.into_val(e),
+                ],
+            },
+            sub_invocations: vec![e],
+        }));
     }
+    e.authorize_as_current_contract(auths);
+    comet_client.join_pool(&expected_tokens, &amounts_in, &e.current_contract_address());
+
+    for (index, token) in tokens.iter().enumerate() {
+        let deposited_tokens = balances.get_unchecked(index as u32)
+            - TokenClient::new(e, &token.address).balance(&e.current_contract_address());
+        deposited.push_back(deposited_tokens);
+    }
+    (deposited, expected_tokens)
 }
 
-/// Execute single sided deposit of the pair token against comet
-///    
+/// Execute single sided deposit of a token against comet. The deposit implicitly swaps part of
+/// the token into the other comet tokens, so the shares minted must be within `max_slippage` of
+/// the spot value of the deposit. The minimum is checked against a quote of the deposit and
+/// passed to comet as the minimum shares out.
+///
 /// Returns (amount of tokens deposited, amount of shares minted)
 ///
 /// ### Arguments
 /// * `e` - The environment
 /// * `comet_client` - The comet client
-/// * `token` - The address of the token to deposit
+/// * `token` - The comet token info of the token to deposit
 /// * `amount` - The amount of tokens to deposit
 /// * `comet_bal` - The current contract balance of comet tokens
+/// * `max_slippage` - The maximum slippage allowed against the spot value of the deposit
+/// * `max_in_ratio` - The maximum portion of the comet balance that can be deposited
+///
+/// ### Panics
+/// If the shares quoted are below the spot value of the deposit less `max_slippage`
 pub fn single_sided_join(
     e: &Env,
     comet_client: &comet::Client,
-    token: &Address,
+    token: &TokenInfo,
     amount: i128,
     comet_bal: i128,
+    max_slippage: i128,
+    max_in_ratio: i128,
 ) -> (i128, i128) {
-    let deposit_amount = amount.min(
-        comet_bal
-            .fixed_mul_floor(MAX_IN_RATIO, SCALAR_7)
-            .unwrap_optimized(),
+    let deposit_amount = max_single_sided_deposit(amount, comet_bal, max_in_ratio);
+    let comet_shares = comet_client.get_total_supply();
+    let min_out = min_shares_out(
+        spot_value_shares(deposit_amount, token.weight, comet_bal, comet_shares),
+        max_slippage,
+    );
+    let quote = quote_single_sided_join(
+        token,
+        deposit_amount,
+        comet_bal,
+        comet_shares,
+        comet_client.get_swap_fee(),
+    );
+    assert_with_error!(
+        e,
+        quote >= min_out,
+        BackstopBootstrapperError::SlippageExceededError
     );
 
     let approval_ledger = (e.ledger().sequence() / 100000 + 1) * 100000;
@@ -138,13 +220,13 @@ pub fn single_sided_join(
         &e,
         InvokerContractAuthEntry::Contract(SubContractInvocation {
             context: ContractContext {
-                contract: token.clone(),
+                contract: token.address.clone(),
                 fn_name: Symbol::new(&e, "approve"),
                 args: vec![
                     &e,
                     e.current_contract_address().into_val(e),
                     storage::get_backstop_token(e).into_val(e),
-                    amount.into_val(e),
+                    deposit_amount.into_val(e),
                     approval_ledger.into_val(e),
                 ],
             },
@@ -152,10 +234,217 @@ pub fn single_sided_join(
         }),
     ]);
     let tokens_minted = comet_client.dep_tokn_amt_in_get_lp_tokns_out(
-        &token,
+        &token.address,
         &deposit_amount,
-        &0,
+        &min_out,
         &e.current_contract_address(),
     );
     (deposit_amount, tokens_minted)
 }
+
+/// Simulate closing a bootstrap against the current comet state, repeating the close until the
+/// bootstrap would complete
+///
+/// Returns (the bootstrap after the simulated closes, the expected outcome of the closes)
+///
+/// ### Arguments
+/// * `e` - The environment
+/// * `comet_client` - The comet client
+/// * `tokens` - The comet tokens
+/// * `bootstrap` - The bootstrap
+/// * `params` - The protocol parameters
+pub fn simulate_close(
+    e: &Env,
+    comet_client: &comet::Client,
+    tokens: &Vec<TokenInfo>,
+    bootstrap: &Bootstrap,
+    params: &ProtocolParams,
+) -> (Bootstrap, CloseQuote) {
+    let mut bootstrap = bootstrap.clone();
+    let mut weights: Vec<i128> = Vec::new(e);
+    let mut comet_bals: Vec<i128> = Vec::new(e);
+    for token in tokens.iter() {
+        weights.push_back(token.weight);
+        comet_bals.push_back(TokenClient::new(e, &token.address).balance(&comet_client.address));
+    }
+    bootstrap.data.weights = weights;
+    let mut comet_shares = comet_client.get_total_supply();
+    let swap_fee = comet_client.get_swap_fee();
+    let start_backstop_tokens = bootstrap.data.total_backstop_tokens;
+    let start_keeper_fees = bootstrap.data.keeper_fees;
+
+    let mut closes = 0;
+    while closes < MAX_SIMULATED_CLOSES && !bootstrap.data.is_converted() {
+        closes += 1;
+        let mut all_held = true;
+        for index in 0..tokens.len() {
+            all_held &= bootstrap.amount_held(index) > bootstrap.data.max_dust_amount;
+        }
+        if all_held {
+            let shares = join_pool_shares(&bootstrap, &comet_bals, comet_shares, params);
+            if shares > 0 {
+                let mut deposited: Vec<i128> = Vec::new(e);
+                for index in 0..tokens.len() {
+                    let amount = comet_bals
+                        .get_unchecked(index)
+                        .fixed_mul_ceil(shares, comet_shares)
+                        .unwrap_optimized();
+                    comet_bals.set(index, comet_bals.get_unchecked(index) + amount);
+                    deposited.push_back(amount);
+                }
+                comet_shares += shares;
+                bootstrap.convert(&deposited, shares);
+            }
+        }
+        for index in bootstrap.close_order().iter() {
+            let amount = bootstrap.single_sided_amount(index);
+            if amount > 0 {
+                let comet_bal = comet_bals.get_unchecked(index);
+                let deposited = max_single_sided_deposit(amount, comet_bal, params.max_in_ratio);
+                let minted = quote_single_sided_join(
+                    &tokens.get_unchecked(index),
+                    deposited,
+                    comet_bal,
+                    comet_shares,
+                    swap_fee,
+                );
+                comet_bals.set(index, comet_bal + deposited);
+                comet_shares += minted;
+                bootstrap.convert_single(index, deposited, minted);
+            }
+        }
+    }
+
+    let mut dust: Vec<i128> = Vec::new(e);
+    for index in 0..tokens.len() {
+        dust.push_back(bootstrap.amount_held(index));
+    }
+    let quote = CloseQuote {
+        backstop_tokens: bootstrap.data.total_backstop_tokens - start_backstop_tokens,
+        keeper_fees: bootstrap.data.keeper_fees - start_keeper_fees,
+        dust,
+        closes,
+    };
+    (bootstrap, quote)
+}
+
+/// Estimate the comet shares minted by a single sided deposit, following comet's pool out given
+/// single in calculation
+///
+/// ### Arguments
+/// * `token` - The comet token info of the token to deposit
+/// * `amount` - The amount of tokens to deposit
+/// * `comet_bal` - The comet balance of the token
+/// * `comet_shares` - The total supply of comet shares
+/// * `swap_fee` - The comet swap fee
+pub fn quote_single_sided_join(
+    token: &TokenInfo,
+    amount: i128,
+    comet_bal: i128,
+    comet_shares: i128,
+    swap_fee: i128,
+) -> i128 {
+    // the portion of the deposit implicitly swapped into the other tokens is charged the swap fee
+    let fee = (SCALAR_7 - token.weight)
+        .fixed_mul_floor(swap_fee, SCALAR_7)
+        .unwrap_optimized();
+    let amount_after_fee = amount
+        .fixed_mul_floor(SCALAR_7 - fee, SCALAR_7)
+        .unwrap_optimized();
+    let balance_ratio = (comet_bal + amount_after_fee)
+        .fixed_div_floor(comet_bal, SCALAR_18)
+        .unwrap_optimized();
+    let share_ratio = pow_approx(balance_ratio, token.weight * (SCALAR_18 / SCALAR_7));
+    comet_shares
+        .fixed_mul_floor(share_ratio - SCALAR_18, SCALAR_18)
+        .unwrap_optimized()
+}
+
+/// Approximate `base` raised to the power of `exp`, for an `exp` between 0 and 1, using a
+/// binomial series like comet (18 decimals)
+fn pow_approx(base: i128, exp: i128) -> i128 {
+    let (x, x_negative) = if base >= SCALAR_18 {
+        (base - SCALAR_18, false)
+    } else {
+        (SCALAR_18 - base, true)
+    };
+    let mut term = SCALAR_18;
+    let mut sum = term;
+    let mut negative = false;
+    let mut k: i128 = 1;
+    while term >= POW_PRECISION && k <= 100 {
+        let big_k = k * SCALAR_18;
+        let c = exp - (big_k - SCALAR_18);
+        term = term
+            .fixed_mul_floor(
+                c.abs().fixed_mul_floor(x, SCALAR_18).unwrap_optimized(),
+                SCALAR_18,
+            )
+            .unwrap_optimized()
+            .fixed_div_floor(big_k, SCALAR_18)
+            .unwrap_optimized();
+        if x_negative {
+            negative = !negative;
+        }
+        if c < 0 {
+            negative = !negative;
+        }
+        if negative {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+        k += 1;
+    }
+    sum
+}
+
+/// Get the amount of a token that can be deposited single sided into comet
+///
+/// ### Arguments
+/// * `amount` - The amount of tokens to deposit
+/// * `comet_bal` - The comet balance of the token
+/// * `max_in_ratio` - The maximum portion of the comet balance that can be deposited
+fn max_single_sided_deposit(amount: i128, comet_bal: i128, max_in_ratio: i128) -> i128 {
+    amount.min(
+        comet_bal
+            .fixed_mul_floor(max_in_ratio, SCALAR_7)
+            .unwrap_optimized(),
+    )
+}
+
+/// Calculate the amount of comet shares `amount` tokens are worth at the current spot price
+///
+/// ### Arguments
+/// * `amount` - The amount of tokens
+/// * `weight` - The normalized weight of the token in comet
+/// * `comet_bal` - The comet balance of the token
+/// * `comet_shares` - The total supply of comet shares
+fn spot_value_shares(amount: i128, weight: i128, comet_bal: i128, comet_shares: i128) -> i128 {
+    if amount <= 0 {
+        return 0;
+    }
+    amount
+        .fixed_mul_floor(weight, SCALAR_7)
+        .unwrap_optimized()
+        .fixed_div_floor(comet_bal, SCALAR_7)
+        .unwrap_optimized()
+        .fixed_mul_floor(comet_shares, SCALAR_7)
+        .unwrap_optimized()
+}
+
+/// Calculate the minimum comet shares a deposit must mint to be within `max_slippage` of its
+/// spot value
+///
+/// ### Arguments
+/// * `spot_value` - The spot value of the deposit in comet shares
+/// * `max_slippage` - The maximum slippage allowed. Zero for no minimum.
+fn min_shares_out(spot_value: i128, max_slippage: i128) -> i128 {
+    if max_slippage > 0 {
+        spot_value
+            .fixed_mul_floor(SCALAR_7 - max_slippage, SCALAR_7)
+            .unwrap_optimized()
+    } else {
+        0
+    }
+}
@@ -1,5 +1,16 @@
 /// 1 with 7 decimal places
 pub const SCALAR_7: i128 = 1_0000000;
-/// 0.01 with 7 decimal places. The maximum amount of tokens that can be considered dust.
+/// 0.01 with 7 decimal places. The default maximum amount of tokens that can be considered dust.
 pub const MAX_DUST_AMOUNT: i128 = 0_0100000;
+/// The default maximum portion of a comet token's balance that can be deposited single sided
 pub const MAX_IN_RATIO: i128 = SCALAR_7 / 3;
+/// 0.01 with 7 decimal places. The maximum keeper fee a bootstrap can pay per close.
+pub const MAX_KEEPER_FEE: i128 = 0_0100000;
+/// The maximum number of days in a bootstrap's keeper window
+pub const MAX_KEEPER_WINDOW_DAYS: u32 = 7;
+/// The maximum number of days a bootstrap can lock claimed backstop tokens for
+pub const MAX_LOCK_DAYS: u32 = 365;
+/// The maximum number of days a bootstrapper's backstop tokens can vest over
+pub const MAX_BOOTSTRAPPER_VESTING_DAYS: u32 = 2 * 365;
+/// The maximum number of bootstraps returned by a single `list_bootstraps` call
+pub const MAX_LIST_LIMIT: u32 = 50;
@@ -1,11 +1,18 @@
 use crate::{
     bootstrap::Bootstrap,
     comet_utils,
-    constants::{MAX_DUST_AMOUNT, SCALAR_7},
+    constants::{
+        MAX_BOOTSTRAPPER_VESTING_DAYS, MAX_KEEPER_FEE, MAX_KEEPER_WINDOW_DAYS, MAX_LIST_LIMIT,
+        MAX_LOCK_DAYS, SCALAR_7,
+    },
     dependencies::comet::Client as CometClient,
     errors::BackstopBootstrapperError,
     storage,
-    types::{BootstrapConfig, BootstrapData, BootstrapStatus, DepositData, TokenInfo},
+    types::{
+        BootstrapConfig, BootstrapData, BootstrapFilter, BootstrapStatus, ClaimDestination,
+        CloseQuote, DepositData, DustData, ProtocolParams, TokenInfo, TokenLiability, UserPosition,
+        VestingData,
+    },
 };
 
 use blend_contract_sdk::{backstop, pool_factory};
@@ -16,7 +23,7 @@ use soroban_sdk::{
     contract, contractimpl, panic_with_error,
     token::TokenClient,
     unwrap::UnwrapOptimized,
-    vec, Address, Env, IntoVal, Symbol, Vec,
+    vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
 };
 
 #[contract]
@@ -27,44 +34,123 @@ impl BackstopBootstrapper {
     /// Initialize the contract
     ///
     /// ### Arguments
+    /// * `admin` - The admin address
     /// * `backstop` - The backstop address
     /// * `backstop_token` - The backstop token address
     /// * `pool_factory_address` - The pool factory address
+    /// * `params` - The protocol parameters
     ///
     /// ### Panics
     /// * `AlreadyInitializedError` - If the contract has already been initialized
+    /// * `InvalidProtocolParams` - If the protocol parameters are out of bounds
     pub fn initialize(
         e: Env,
+        admin: Address,
         backstop: Address,
         backstop_token: Address,
         pool_factory_address: Address,
+        params: ProtocolParams,
     ) {
         if storage::get_is_init(&e) {
             panic_with_error!(&e, BackstopBootstrapperError::AlreadyInitializedError);
         }
+        require_valid_params(&e, &params);
         storage::set_is_init(&e);
+        storage::set_params(&e, &params);
+        storage::set_admin(&e, &admin);
         storage::set_backstop(&e, backstop);
         storage::set_backstop_token(&e, backstop_token.clone());
         storage::set_pool_factory(&e, pool_factory_address);
-        let backstop_token = CometClient::new(&e, &backstop_token);
-        let tokens = backstop_token.get_tokens();
-        let mut token_data: Vec<TokenInfo> = Vec::new(&e);
-        for address in tokens.iter() {
-            let weight = backstop_token.get_normalized_weight(&address);
-            token_data.push_back(TokenInfo { address, weight });
-        }
+        let token_data = comet_utils::get_token_data(&e, &CometClient::new(&e, &backstop_token));
         storage::set_comet_token_data(&e, &token_data);
         storage::set_next_id(&e, 0);
     }
 
     //********** Read-Only ***********//
 
+    /// Fetch the admin address
+    pub fn get_admin(e: Env) -> Address {
+        storage::get_admin(&e)
+    }
+
     /// Fetch data for a bootstrap
     ///
     /// ### Arguments
     /// * `id` - The id of the bootstrap
     pub fn get_bootstrap(e: Env, id: u32) -> Bootstrap {
-        Bootstrap::load(&e, id)
+        Bootstrap::load_current(&e, id)
+    }
+
+    /// List bootstraps matching all of the filters, in order of id
+    ///
+    /// Returns up to `limit` bootstraps with an id of at least `start`. To fetch the next page,
+    /// call again with `start` set to one past the id of the last bootstrap returned.
+    ///
+    /// ### Arguments
+    /// * `start` - The id to start listing from
+    /// * `limit` - The maximum number of bootstraps to return, capped at 50
+    /// * `filters` - The filters bootstraps must match. Empty to match all bootstraps.
+    pub fn list_bootstraps(
+        e: Env,
+        start: u32,
+        limit: u32,
+        filters: Vec<BootstrapFilter>,
+    ) -> Vec<Bootstrap> {
+        let limit = limit.min(MAX_LIST_LIMIT);
+        // scan the ids of a bootstrapper or pool index if filtered by one
+        let mut ids: Option<Vec<u32>> = None;
+        for filter in filters.iter() {
+            match filter {
+                BootstrapFilter::Bootstrapper(bootstrapper) => {
+                    ids = Some(storage::get_bootstrapper_ids(&e, &bootstrapper));
+                    break;
+                }
+                BootstrapFilter::Pool(pool) => {
+                    ids = Some(storage::get_pool_ids(&e, &pool));
+                }
+                _ => {}
+            }
+        }
+        let mut bootstraps: Vec<Bootstrap> = Vec::new(&e);
+        // add the bootstrap if it matches the filters. Returns false once the limit is reached.
+        let mut scan = |id: u32| -> bool {
+            if bootstraps.len() >= limit {
+                return false;
+            }
+            let bootstrap = Bootstrap::load_current(&e, id);
+            let is_match = filters.iter().all(|filter| match filter {
+                BootstrapFilter::Status(status) => bootstrap.data.status == status,
+                BootstrapFilter::Pool(pool) => bootstrap.config.pool == pool,
+                BootstrapFilter::Bootstrapper(bootstrapper) => {
+                    bootstrap.config.bootstrapper == bootstrapper
+                }
+                BootstrapFilter::TokenIndex(token_index) => {
+                    bootstrap.config.token_index == token_index
+                }
+            });
+            if is_match {
+                bootstraps.push_back(bootstrap);
+            }
+            true
+        };
+        match ids {
+            Some(ids) => {
+                for id in ids.iter() {
+                    if id >= start && !scan(id) {
+                        break;
+                    }
+                }
+            }
+            // otherwise scan the id range directly
+            None => {
+                for id in start..storage::get_next_id(&e) {
+                    if !scan(id) {
+                        break;
+                    }
+                }
+            }
+        }
+        bootstraps
     }
 
     /// Fetch the next bootstrap's ID. The previous (and most recently created) bootsrap's ID will
@@ -73,6 +159,33 @@ impl BackstopBootstrapper {
         storage::get_next_id(&e)
     }
 
+    /// Fetch the protocol parameters
+    pub fn get_params(e: Env) -> ProtocolParams {
+        storage::get_params(&e)
+    }
+
+    /// Fetch the cached comet token data
+    pub fn get_token_data(e: Env) -> Vec<TokenInfo> {
+        storage::get_comet_token_data(&e)
+    }
+
+    /// Check if an address is an allowlisted keeper
+    ///
+    /// ### Arguments
+    /// * `keeper` - The address to check
+    pub fn is_keeper(e: Env, keeper: Address) -> bool {
+        storage::get_is_keeper(&e, &keeper)
+    }
+
+    /// Fetch the vesting backstop tokens of a user for a bootstrap, if any
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    /// * `user` - The address of the user
+    pub fn get_vesting(e: Env, id: u32, user: Address) -> Option<VestingData> {
+        storage::get_vesting(&e, id, &user)
+    }
+
     /// Fetch a deposit for a user in a bootstrap
     ///
     /// ### Arguments
@@ -82,6 +195,332 @@ impl BackstopBootstrapper {
         storage::get_deposit(&e, id, &user)
     }
 
+    /// Fetch the positions of a user in the bootstraps they have joined and not yet claimed or
+    /// been refunded from
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    pub fn get_user_positions(e: Env, user: Address) -> Vec<UserPosition> {
+        let mut positions: Vec<UserPosition> = Vec::new(&e);
+        for id in storage::get_user_ids(&e, &user).iter() {
+            let bootstrap = Bootstrap::load_current(&e, id);
+            let deposit = storage::get_deposit(&e, id, &user);
+            let status = bootstrap.data.status;
+            let can_claim = status == BootstrapStatus::Completed
+                || status == BootstrapStatus::PartiallyCompleted;
+            let claimable = if can_claim && !deposit.claimed {
+                bootstrap.claimable(&deposit)
+            } else {
+                0
+            };
+            let refundable = if (status == BootstrapStatus::Cancelled && !deposit.refunded)
+                || (status == BootstrapStatus::PartiallyCompleted && !deposit.claimed)
+            {
+                bootstrap.refundable(&e, &deposit)
+            } else {
+                Vec::new(&e)
+            };
+            positions.push_back(UserPosition {
+                id,
+                status: bootstrap.data.status,
+                deposit,
+                claimable,
+                refundable,
+            });
+        }
+        positions
+    }
+
+    /// Simulate closing a bootstrap against the current comet state. Closes are repeated until
+    /// the bootstrap would complete.
+    ///
+    /// Returns the expected outcome of closing the bootstrap
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    ///
+    /// ### Panics
+    /// * `InvalidBootstrapStatus` - If the bootstrap is not active or closing
+    pub fn simulate_close(e: Env, id: u32) -> CloseQuote {
+        let bootstrap = Bootstrap::load_current(&e, id);
+        assert_with_error!(
+            e,
+            bootstrap.data.status == BootstrapStatus::Active
+                || bootstrap.data.status == BootstrapStatus::Closing,
+            BackstopBootstrapperError::InvalidBootstrapStatus
+        );
+        let (_, quote) = comet_utils::simulate_close(
+            &e,
+            &CometClient::new(&e, &storage::get_backstop_token(&e)),
+            &storage::get_comet_token_data(&e),
+            &bootstrap,
+            &storage::get_params(&e),
+        );
+        quote
+    }
+
+    /// Quote the backstop tokens a user can claim from a bootstrap. If the bootstrap has not
+    /// completed, the quote assumes it is closed against the current comet state. Backstop
+    /// tokens released over a lock period are included in full. Cancelled bootstraps can't be
+    /// claimed, so they are quoted as zero.
+    ///
+    /// Returns the expected amount of backstop tokens claimed
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    /// * `user` - The address of the user
+    pub fn quote_claim(e: Env, id: u32, user: Address) -> i128 {
+        let mut bootstrap = Bootstrap::load_current(&e, id);
+        if bootstrap.data.status == BootstrapStatus::Cancelled {
+            return 0;
+        }
+        if bootstrap.data.status == BootstrapStatus::Active
+            || bootstrap.data.status == BootstrapStatus::Closing
+        {
+            (bootstrap, _) = comet_utils::simulate_close(
+                &e,
+                &CometClient::new(&e, &storage::get_backstop_token(&e)),
+                &storage::get_comet_token_data(&e),
+                &bootstrap,
+                &storage::get_params(&e),
+            );
+        }
+        if bootstrap.config.bootstrapper == user {
+            if storage::get_claimed(&e, id) {
+                0
+            } else {
+                bootstrap.data.bootstrap_backstop_tokens
+            }
+        } else {
+            let deposit = storage::get_deposit(&e, id, &user);
+            if deposit.claimed {
+                0
+            } else {
+                bootstrap.claimable(&deposit)
+            }
+        }
+    }
+
+    /// Quote the funds a user would be refunded from a bootstrap if it is cancelled. If the
+    /// bootstrap is partially completed, quotes the unconverted tokens returned by `claim`.
+    ///
+    /// Returns the expected amount of funds returned, summed across deposited tokens for
+    /// depositors
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    /// * `user` - The address of the user
+    pub fn quote_refund(e: Env, id: u32, user: Address) -> i128 {
+        let bootstrap = Bootstrap::load_current(&e, id);
+        let is_partial = bootstrap.data.status == BootstrapStatus::PartiallyCompleted;
+        if bootstrap.config.bootstrapper == user {
+            let done = if is_partial {
+                storage::get_claimed(&e, id)
+            } else {
+                storage::get_refunded(&e, id)
+            };
+            if done {
+                0
+            } else {
+                bootstrap.data.bootstrap_amount
+            }
+        } else {
+            let deposit = storage::get_deposit(&e, id, &user);
+            if is_partial {
+                if deposit.claimed {
+                    0
+                } else {
+                    bootstrap.refundable(&e, &deposit).iter().sum::<i128>()
+                }
+            } else if deposit.refunded {
+                0
+            } else {
+                bootstrap.refundable(&e, &deposit).iter().sum::<i128>() + deposit.backstop_tokens
+            }
+        }
+    }
+
+    /// Fetch the leftover tokens of a bootstrap that `sweep_dust` would currently send to the
+    /// bootstrapper
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    pub fn get_dust(e: Env, id: u32) -> DustData {
+        Bootstrap::load_current(&e, id).dust(&e)
+    }
+
+    /// Fetch the tokens held for all bootstraps against the contract's balance of each token.
+    /// The contract is solvent if every balance covers its liabilities. Every bootstrap is
+    /// loaded, so this is intended to be simulated rather than invoked.
+    ///
+    /// Returns the liabilities of each comet token, indexed by comet token index, followed by
+    /// the liabilities of the backstop token
+    pub fn get_liabilities(e: Env) -> Vec<TokenLiability> {
+        let comet_tokens = storage::get_comet_token_data(&e);
+        let mut amounts: Vec<i128> = Vec::new(&e);
+        for _ in 0..comet_tokens.len() {
+            amounts.push_back(0);
+        }
+        let mut backstop_tokens = 0;
+        for id in 0..storage::get_next_id(&e) {
+            let bootstrap = Bootstrap::load(&e, id);
+            for index in 0..comet_tokens.len() {
+                let amount = amounts.get_unchecked(index);
+                amounts.set(index, amount + bootstrap.amount_unpaid(index));
+            }
+            backstop_tokens += bootstrap.backstop_tokens_unpaid();
+        }
+
+        let mut liabilities: Vec<TokenLiability> = Vec::new(&e);
+        for (token, amount) in comet_tokens.iter().zip(amounts.iter()) {
+            liabilities.push_back(TokenLiability {
+                balance: TokenClient::new(&e, &token.address)
+                    .balance(&e.current_contract_address()),
+                token: token.address,
+                liabilities: amount,
+            });
+        }
+        let backstop_token = storage::get_backstop_token(&e);
+        liabilities.push_back(TokenLiability {
+            balance: TokenClient::new(&e, &backstop_token).balance(&e.current_contract_address()),
+            token: backstop_token,
+            liabilities: backstop_tokens,
+        });
+        liabilities
+    }
+
+    //********** Admin ***********//
+
+    /// (Admin only) Set a new address as the admin of this contract
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The new admin address
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the new admin has not authorized the handoff
+    pub fn set_admin(e: Env, new_admin: Address) {
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+        new_admin.require_auth();
+
+        storage::set_admin(&e, &new_admin);
+        e.events()
+            .publish((Symbol::new(&e, "set_admin"), admin), new_admin);
+    }
+
+    /// (Admin only) Upgrade the contract to a new WASM
+    ///
+    /// ### Arguments
+    /// * `wasm_hash` - The hash of the installed WASM to upgrade to
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    pub fn upgrade(e: Env, wasm_hash: BytesN<32>) {
+        storage::get_admin(&e).require_auth();
+
+        e.deployer().update_current_contract_wasm(wasm_hash.clone());
+        e.events().publish((Symbol::new(&e, "upgrade"),), wasm_hash);
+    }
+
+    /// (Admin only) Add or remove an address from the keeper allowlist. Keepers can close any
+    /// bootstrap during its keeper window.
+    ///
+    /// ### Arguments
+    /// * `keeper` - The keeper address
+    /// * `is_keeper` - True to add the keeper, false to remove it
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    pub fn set_keeper(e: Env, keeper: Address, is_keeper: bool) {
+        storage::get_admin(&e).require_auth();
+
+        storage::set_is_keeper(&e, &keeper, is_keeper);
+        e.events()
+            .publish((Symbol::new(&e, "set_keeper"), keeper), is_keeper);
+    }
+
+    /// (Admin only) Set the protocol parameters. Changes apply to existing bootstraps, except
+    /// for the duration bounds, which are only checked when a bootstrap is created or updated,
+    /// and `close_expiry` and `max_dust_amount`, which are snapshotted into each bootstrap when
+    /// it is created.
+    ///
+    /// ### Arguments
+    /// * `params` - The new protocol parameters
+    ///
+    /// ### Panics
+    /// * `InvalidProtocolParams` - If the protocol parameters are out of bounds
+    pub fn set_params(e: Env, params: ProtocolParams) {
+        storage::get_admin(&e).require_auth();
+        require_valid_params(&e, &params);
+
+        storage::set_params(&e, &params);
+        e.events().publish((Symbol::new(&e, "set_params"),), params);
+    }
+
+    /// (Admin only) Migrate the contract to a new set of Blend contracts. The underlying
+    /// tokens of the new backstop token must match the current ones, so any tokens held for
+    /// in-flight bootstraps remain valid. Backstop tokens held for bootstraps are held in the
+    /// current backstop token, so the backstop token can only be changed once none are held.
+    ///
+    /// ### Arguments
+    /// * `backstop` - The new backstop address
+    /// * `backstop_token` - The new backstop token address
+    /// * `pool_factory_address` - The new pool factory address
+    ///
+    /// ### Panics
+    /// * `UnauthorizedError` - If the caller is not the admin
+    /// * `InvalidBackstopTokenError` - If the backstop token does not match the backstop, or
+    ///                                 its underlying tokens differ from the current ones
+    /// * `BackstopTokensHeldError` - If the backstop token changes while backstop tokens are
+    ///                               still held for a bootstrap
+    pub fn migrate(
+        e: Env,
+        backstop: Address,
+        backstop_token: Address,
+        pool_factory_address: Address,
+    ) {
+        storage::get_admin(&e).require_auth();
+
+        assert_with_error!(
+            e,
+            backstop::Client::new(&e, &backstop).backstop_token() == backstop_token,
+            BackstopBootstrapperError::InvalidBackstopTokenError
+        );
+        let cur_token_data = storage::get_comet_token_data(&e);
+        let new_token_data =
+            comet_utils::get_token_data(&e, &CometClient::new(&e, &backstop_token));
+        assert_with_error!(
+            e,
+            cur_token_data.len() == new_token_data.len(),
+            BackstopBootstrapperError::InvalidBackstopTokenError
+        );
+        for (cur_info, new_info) in cur_token_data.iter().zip(new_token_data.iter()) {
+            assert_with_error!(
+                e,
+                cur_info.address == new_info.address,
+                BackstopBootstrapperError::InvalidBackstopTokenError
+            );
+        }
+        if backstop_token != storage::get_backstop_token(&e) {
+            for id in 0..storage::get_next_id(&e) {
+                assert_with_error!(
+                    e,
+                    Bootstrap::load(&e, id).backstop_tokens_unpaid() == 0,
+                    BackstopBootstrapperError::BackstopTokensHeldError
+                );
+            }
+        }
+
+        storage::set_backstop(&e, backstop.clone());
+        storage::set_backstop_token(&e, backstop_token.clone());
+        storage::set_pool_factory(&e, pool_factory_address.clone());
+        storage::set_comet_token_data(&e, &new_token_data);
+        e.events().publish(
+            (Symbol::new(&e, "migrate"),),
+            (backstop, backstop_token, pool_factory_address),
+        );
+    }
+
     //********** Read-Write ***********//
 
     /// Add a new bootstrap
@@ -92,9 +531,11 @@ impl BackstopBootstrapper {
     /// * `config` - The configuration for the bootstrap
     pub fn bootstrap(e: Env, config: BootstrapConfig) -> u32 {
         config.bootstrapper.require_auth();
+        let params = storage::get_params(&e);
+        let comet_tokens = comet_utils::sync_token_data(&e);
         assert_with_error!(
             e,
-            config.token_index == 0 || config.token_index == 1,
+            config.token_index < comet_tokens.len(),
             BackstopBootstrapperError::InvalidBootstrapToken
         );
         assert_with_error!(
@@ -104,13 +545,77 @@ impl BackstopBootstrapper {
         );
         assert_with_error!(
             e,
-            config.pair_min >= 0,
-            BackstopBootstrapperError::NegativeAmountError
+            config.pair_min.len() == comet_tokens.len(),
+            BackstopBootstrapperError::InvalidPairMin
+        );
+        assert_with_error!(
+            e,
+            config.pair_max.len() == comet_tokens.len(),
+            BackstopBootstrapperError::InvalidPairMax
+        );
+        for index in 0..comet_tokens.len() {
+            let pair_min = config.pair_min.get_unchecked(index);
+            let pair_max = config.pair_max.get_unchecked(index);
+            assert_with_error!(
+                e,
+                pair_min >= 0,
+                BackstopBootstrapperError::NegativeAmountError
+            );
+            assert_with_error!(
+                e,
+                pair_max == 0 || pair_max >= pair_min,
+                BackstopBootstrapperError::InvalidPairMax
+            );
+            // the bootstrap token can only be deposited into two-sided bootstraps
+            assert_with_error!(
+                e,
+                index != config.token_index || config.two_sided || (pair_min == 0 && pair_max == 0),
+                BackstopBootstrapperError::InvalidPairToken
+            );
+        }
+        assert_with_error!(
+            e,
+            (0..SCALAR_7).contains(&config.max_slippage),
+            BackstopBootstrapperError::InvalidMaxSlippage
+        );
+        assert_with_error!(
+            e,
+            (0..SCALAR_7).contains(&config.max_price_deviation),
+            BackstopBootstrapperError::InvalidMaxPriceDeviation
+        );
+        // slippage is measured against the spot price, so a skewed comet must be caught by the
+        // price deviation check
+        assert_with_error!(
+            e,
+            config.max_slippage == 0 || config.max_price_deviation > 0,
+            BackstopBootstrapperError::InvalidMaxSlippage
+        );
+        assert_with_error!(
+            e,
+            config.keeper_window <= MAX_KEEPER_WINDOW_DAYS * params.one_day_ledgers,
+            BackstopBootstrapperError::InvalidKeeperWindow
+        );
+        assert_with_error!(
+            e,
+            (0..=MAX_KEEPER_FEE).contains(&config.keeper_fee),
+            BackstopBootstrapperError::InvalidKeeperFee
+        );
+        assert_with_error!(
+            e,
+            config.lock_ledgers <= MAX_LOCK_DAYS * params.one_day_ledgers,
+            BackstopBootstrapperError::InvalidLockLedgers
+        );
+        assert_with_error!(
+            e,
+            config.bootstrapper_vesting_ledgers
+                <= MAX_BOOTSTRAPPER_VESTING_DAYS * params.one_day_ledgers
+                && config.bootstrapper_cliff_ledgers <= config.bootstrapper_vesting_ledgers,
+            BackstopBootstrapperError::InvalidBootstrapperVesting
         );
         let duration = config.close_ledger.saturating_sub(e.ledger().sequence());
         assert_with_error!(
             e,
-            duration >= storage::ONE_DAY_LEDGERS && duration <= 14 * storage::ONE_DAY_LEDGERS,
+            (params.min_duration..=params.max_duration).contains(&duration),
             BackstopBootstrapperError::InvalidCloseLedger
         );
         assert_with_error!(
@@ -121,7 +626,19 @@ impl BackstopBootstrapper {
 
         // transfer the bootstrapped tokens into the contract and create the bootstrap
         let id = storage::get_next_id(&e);
-        let token_info = storage::get_comet_token_data(&e).get_unchecked(config.token_index);
+        let token_info = comet_tokens.get_unchecked(config.token_index);
+        let pair_info = comet_tokens.get_unchecked(if config.token_index == 0 { 1 } else { 0 });
+        let mut zeros: Vec<i128> = Vec::new(&e);
+        let mut weights: Vec<i128> = Vec::new(&e);
+        for token in comet_tokens.iter() {
+            zeros.push_back(0);
+            weights.push_back(token.weight);
+        }
+        let spot_price = comet_utils::get_spot_price(
+            &CometClient::new(&e, &storage::get_backstop_token(&e)),
+            &token_info.address,
+            &pair_info.address,
+        );
         TokenClient::new(&e, &token_info.address).transfer(
             &config.bootstrapper,
             &e.current_contract_address(),
@@ -132,13 +649,35 @@ impl BackstopBootstrapper {
             &e,
             id,
             &BootstrapData {
+                status: BootstrapStatus::Active,
                 bootstrap_amount: config.amount,
-                pair_amount: 0,
+                pair_amounts: zeros.clone(),
                 total_backstop_tokens: 0,
-                total_pair: 0,
+                backstop_token_deposits: 0,
+                bootstrap_backstop_tokens: 0,
+                pair_backstop_tokens: zeros.clone(),
+                keeper_fees: 0,
+                pair_deposits: zeros.clone(),
+                weights,
+                close_expiry: params.close_expiry,
+                max_dust_amount: params.max_dust_amount,
+                price_cumulative: 0,
+                last_price: spot_price,
+                last_price_ledger: e.ledger().sequence(),
+                price_start_ledger: e.ledger().sequence(),
+                depositors: 0,
+                claimed_backstop_tokens: 0,
+                paid_backstop_tokens: 0,
+                paid_amounts: zeros,
             },
         );
         storage::set_next_id(&e, id + 1);
+        let mut pool_ids = storage::get_pool_ids(&e, &config.pool);
+        pool_ids.push_back(id);
+        storage::set_pool_ids(&e, &config.pool, &pool_ids);
+        let mut bootstrapper_ids = storage::get_bootstrapper_ids(&e, &config.bootstrapper);
+        bootstrapper_ids.push_back(id);
+        storage::set_bootstrapper_ids(&e, &config.bootstrapper, &bootstrapper_ids);
 
         e.events().publish(
             (Symbol::new(&e, "bootstrap"), config.bootstrapper, id),
@@ -147,48 +686,293 @@ impl BackstopBootstrapper {
         id
     }
 
-    /// Join a bootstrap by depositing a given amount of pair tokens
+    /// Update an active bootstrap. Only changes that favor depositors are allowed.
     ///
-    /// Returns the total amount of pair tokens deposited by `from` in this bootstrap
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    /// * `amount` - The additional amount of the bootstrap token to add
+    /// * `pair_min` - The new minimum amount of each pair token, indexed by comet token index.
+    ///                Cannot be raised.
+    /// * `close_ledger` - The new ledger number at which the bootstrap will close. Cannot be
+    ///                    moved earlier.
+    ///
+    /// ### Panics
+    /// * `InvalidBootstrapStatus` - If the bootstrap is not active
+    /// * `NegativeAmountError` - If `amount` or any `pair_min` is negative
+    /// * `InvalidPairMin` - If any `pair_min` is raised, or `pair_min` is not one per comet token
+    /// * `InvalidCloseLedger` - If `close_ledger` is moved earlier or outside the allowed duration
+    pub fn update_bootstrap(e: Env, id: u32, amount: i128, pair_min: Vec<i128>, close_ledger: u32) {
+        let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.config.bootstrapper.require_auth();
+        bootstrap.update_status(&e);
+        assert_with_error!(
+            e,
+            bootstrap.data.status == BootstrapStatus::Active,
+            BackstopBootstrapperError::InvalidBootstrapStatus
+        );
+        assert_with_error!(
+            e,
+            amount >= 0 && pair_min.iter().all(|min| min >= 0),
+            BackstopBootstrapperError::NegativeAmountError
+        );
+        assert_with_error!(
+            e,
+            pair_min.len() == bootstrap.config.pair_min.len()
+                && pair_min
+                    .iter()
+                    .zip(bootstrap.config.pair_min.iter())
+                    .all(|(new_min, min)| new_min <= min),
+            BackstopBootstrapperError::InvalidPairMin
+        );
+        if close_ledger != bootstrap.config.close_ledger {
+            let params = storage::get_params(&e);
+            let duration = close_ledger.saturating_sub(e.ledger().sequence());
+            assert_with_error!(
+                e,
+                close_ledger > bootstrap.config.close_ledger
+                    && (params.min_duration..=params.max_duration).contains(&duration),
+                BackstopBootstrapperError::InvalidCloseLedger
+            );
+        }
+
+        if amount > 0 {
+            let token_info =
+                storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index);
+            TokenClient::new(&e, &token_info.address).transfer(
+                &bootstrap.config.bootstrapper,
+                &e.current_contract_address(),
+                &amount,
+            );
+            bootstrap.config.amount += amount;
+            bootstrap.data.bootstrap_amount += amount;
+            bootstrap.store(&e);
+        }
+        bootstrap.config.pair_min = pair_min.clone();
+        bootstrap.config.close_ledger = close_ledger;
+        storage::set_bootstrap_config(&e, id, &bootstrap.config);
+
+        e.events().publish(
+            (
+                Symbol::new(&e, "bootstrap_update"),
+                bootstrap.config.bootstrapper,
+                id,
+            ),
+            (amount, pair_min, close_ledger),
+        );
+    }
+
+    /// Cancel an active bootstrap. Only the bootstrapper can cancel, and only while the
+    /// bootstrap is still active. The bootstrapper and any depositors can then `refund`.
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    ///
+    /// ### Panics
+    /// * `InvalidBootstrapStatus` - If the bootstrap is not active
+    pub fn cancel(e: Env, id: u32) {
+        let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.config.bootstrapper.require_auth();
+        bootstrap.update_status(&e);
+        assert_with_error!(
+            e,
+            bootstrap.data.status == BootstrapStatus::Active,
+            BackstopBootstrapperError::InvalidBootstrapStatus
+        );
+
+        bootstrap.set_status(&e, BootstrapStatus::Cancelled);
+        bootstrap.store(&e);
+        e.events().publish(
+            (
+                Symbol::new(&e, "bootstrap_cancel"),
+                bootstrap.config.bootstrapper,
+                id,
+            ),
+            bootstrap.data.pair_deposits,
+        );
+    }
+
+    /// Advance a bootstrap through any time-based status transitions it is due, such as reaching
+    /// its close ledger. If the bootstrap is active or closing, the comet spot price is recorded
+    /// into its time-weighted average price. Anyone can poke a bootstrap.
+    ///
+    /// Returns the status of the bootstrap
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    pub fn poke(e: Env, id: u32) -> BootstrapStatus {
+        let mut bootstrap = Bootstrap::load(&e, id);
+        let mut changed = bootstrap.update_status(&e);
+        if bootstrap.data.status == BootstrapStatus::Active
+            || bootstrap.data.status == BootstrapStatus::Closing
+        {
+            record_spot_price(
+                &e,
+                &mut bootstrap,
+                &CometClient::new(&e, &storage::get_backstop_token(&e)),
+                &storage::get_comet_token_data(&e),
+            );
+            changed = true;
+        }
+        if changed {
+            bootstrap.store(&e);
+        }
+        bootstrap.data.status
+    }
+
+    /// Send the leftover tokens of a completed, partially completed or cancelled bootstrap to
+    /// the bootstrapper. Anyone can sweep a bootstrap. See `get_dust` for what is swept.
+    ///
+    /// Returns the amount of each token swept
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the bootstrap
+    ///
+    /// ### Panics
+    /// * `InvalidBootstrapStatus` - If the bootstrap is still active or closing
+    pub fn sweep_dust(e: Env, id: u32) -> DustData {
+        let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.update_status(&e);
+        assert_with_error!(
+            e,
+            bootstrap.data.status != BootstrapStatus::Active
+                && bootstrap.data.status != BootstrapStatus::Closing,
+            BackstopBootstrapperError::InvalidBootstrapStatus
+        );
+
+        let dust = bootstrap.dust(&e);
+        let bootstrapper = bootstrap.config.bootstrapper.clone();
+        let comet_tokens = storage::get_comet_token_data(&e);
+        for (index, amount) in dust.amounts.iter().enumerate() {
+            if amount > 0 {
+                let index = index as u32;
+                let paid = bootstrap.data.paid_amounts.get_unchecked(index);
+                bootstrap.data.paid_amounts.set(index, paid + amount);
+                TokenClient::new(&e, &comet_tokens.get_unchecked(index).address).transfer(
+                    &e.current_contract_address(),
+                    &bootstrapper,
+                    &amount,
+                );
+            }
+        }
+        if dust.backstop_tokens > 0 {
+            bootstrap.data.claimed_backstop_tokens += dust.backstop_tokens;
+            bootstrap.data.paid_backstop_tokens += dust.backstop_tokens;
+            TokenClient::new(&e, &storage::get_backstop_token(&e)).transfer(
+                &e.current_contract_address(),
+                &bootstrapper,
+                &dust.backstop_tokens,
+            );
+        }
+        bootstrap.store(&e);
+
+        e.events().publish(
+            (Symbol::new(&e, "sweep_dust"), bootstrapper, id),
+            (dust.amounts.clone(), dust.backstop_tokens),
+        );
+        dust
+    }
+
+    /// Refresh the cached comet token data from the backstop token. Emits an event
+    /// if the tokens or weights have changed.
+    ///
+    /// Returns the current comet token data
+    pub fn sync_token_data(e: Env) -> Vec<TokenInfo> {
+        comet_utils::sync_token_data(&e)
+    }
+
+    /// Join a bootstrap by depositing a given amount of a pair token. Any comet token other than
+    /// the bootstrap token can be used as a pair token. If the bootstrap is two-sided, the
+    /// bootstrap token can be deposited as well.
+    ///
+    /// Returns the total amount of the token deposited by `from` in this bootstrap
     ///
     /// ### Arguments
     /// * `from` - The address of the user joining the bootstrap
     /// * `id` - The bootstrap id to join
+    /// * `token_index` - The comet token index of the token to join with
     /// * `amount` - The amount of tokens to join with
-    pub fn join(e: Env, from: Address, id: u32, amount: i128) -> i128 {
+    ///
+    /// ### Panics
+    /// * `InvalidPairToken` - If `token_index` is not a comet token, or is the bootstrap token of
+    ///                        a bootstrap that is not two-sided
+    /// * `BootstrapperDepositError` - If `from` is the bootstrapper
+    pub fn join(e: Env, from: Address, id: u32, token_index: u32, amount: i128) -> i128 {
         from.require_auth();
         let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.update_status(&e);
         assert_with_error!(
             e,
-            bootstrap.status == BootstrapStatus::Active,
+            bootstrap.data.status == BootstrapStatus::Active,
             BackstopBootstrapperError::InvalidBootstrapStatus
         );
+        assert_with_error!(
+            e,
+            from != bootstrap.config.bootstrapper,
+            BackstopBootstrapperError::BootstrapperDepositError
+        );
+        let comet_tokens = storage::get_comet_token_data(&e);
+        assert_with_error!(
+            e,
+            (token_index != bootstrap.config.token_index || bootstrap.config.two_sided)
+                && token_index < bootstrap.data.pair_amounts.len(),
+            BackstopBootstrapperError::InvalidPairToken
+        );
+
+        bootstrap.join(token_index, amount);
+        let pair_max = bootstrap.config.pair_max.get_unchecked(token_index);
+        assert_with_error!(
+            e,
+            pair_max == 0 || bootstrap.data.pair_deposits.get_unchecked(token_index) <= pair_max,
+            BackstopBootstrapperError::PairMaxExceededError
+        );
 
-        let pair_token =
-            storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index ^ 1);
+        let pair_token = comet_tokens.get_unchecked(token_index);
         TokenClient::new(&e, &pair_token.address).transfer(
             &from,
             &e.current_contract_address(),
             &amount,
         );
 
-        bootstrap.join(amount);
+        record_spot_price(
+            &e,
+            &mut bootstrap,
+            &CometClient::new(&e, &storage::get_backstop_token(&e)),
+            &comet_tokens,
+        );
+        if storage::add_user_id(&e, &from, id) {
+            bootstrap.data.depositors += 1;
+        }
         bootstrap.store(&e);
         let mut deposit_data = storage::get_deposit(&e, id, &from);
-        deposit_data.amount += amount;
+        deposit_data.add(token_index, amount);
         storage::set_deposit(&e, id, &from, deposit_data.clone());
-        deposit_data.amount
+
+        e.events().publish(
+            (Symbol::new(&e, "join"), from, id),
+            (
+                token_index,
+                amount,
+                deposit_data.clone(),
+                bootstrap.data.pair_amounts.get_unchecked(token_index),
+            ),
+        );
+        deposit_data.amount(token_index)
     }
 
-    /// Exits a bootstrap by withdrawing a given amount of pair tokens
+    /// Exits a bootstrap by withdrawing a given amount of a deposited token
     ///
-    /// Returns the remaining amount of pair tokens deposited by `from` in this bootstrap
+    /// Returns the remaining amount of the token deposited by `from` in this bootstrap
     ///
     /// ### Arguments
     /// * `from` - The address of the user joining the bootstrap
     /// * `id` - The bootstrap id to join
+    /// * `token_index` - The comet token index of the token to exit with
     /// * `amount` - The amount of tokens to join with
-    pub fn exit(e: Env, from: Address, id: u32, amount: i128) -> i128 {
+    ///
+    /// ### Panics
+    /// * `InvalidPairToken` - If `token_index` is not a comet token, or is the bootstrap token of
+    ///                        a bootstrap that is not two-sided
+    pub fn exit(e: Env, from: Address, id: u32, token_index: u32, amount: i128) -> i128 {
         from.require_auth();
         assert_with_error!(
             e,
@@ -196,22 +980,27 @@ impl BackstopBootstrapper {
             BackstopBootstrapperError::NegativeAmountError
         );
         let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.update_status(&e);
         assert_with_error!(
             e,
-            bootstrap.status == BootstrapStatus::Active,
+            bootstrap.data.status == BootstrapStatus::Active,
             BackstopBootstrapperError::InvalidBootstrapStatus
         );
+        assert_with_error!(
+            e,
+            (token_index != bootstrap.config.token_index || bootstrap.config.two_sided)
+                && token_index < bootstrap.data.pair_amounts.len(),
+            BackstopBootstrapperError::InvalidPairToken
+        );
 
-        let pair_token =
-            storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index ^ 1);
+        let pair_token = storage::get_comet_token_data(&e).get_unchecked(token_index);
         let mut deposit_data = storage::get_deposit(&e, id, &from);
-        deposit_data.amount -= amount;
-        bootstrap.exit(amount);
+        deposit_data.add(token_index, -amount);
+        bootstrap.exit(token_index, amount);
         assert_with_error!(
             e,
-            deposit_data.amount >= 0
-                && bootstrap.data.pair_amount >= 0
-                && bootstrap.data.total_pair >= 0,
+            deposit_data.amount(token_index) >= 0
+                && bootstrap.data.pair_amounts.get_unchecked(token_index) >= 0,
             BackstopBootstrapperError::InsufficientDepositError
         );
         TokenClient::new(&e, &pair_token.address).transfer(
@@ -219,87 +1008,259 @@ impl BackstopBootstrapper {
             &from,
             &amount,
         );
+        if deposit_data.is_empty() && storage::remove_user_id(&e, &from, id) {
+            bootstrap.data.depositors -= 1;
+        }
         bootstrap.store(&e);
         storage::set_deposit(&e, id, &from, deposit_data.clone());
-        deposit_data.amount
+
+        e.events().publish(
+            (Symbol::new(&e, "exit"), from, id),
+            (
+                token_index,
+                amount,
+                deposit_data.clone(),
+                bootstrap.data.pair_amounts.get_unchecked(token_index),
+            ),
+        );
+        deposit_data.amount(token_index)
     }
 
-    /// Close the bootstrap by depositing bootstrapping tokens into the comet
+    /// Join a bootstrap by depositing a given amount of backstop tokens. The backstop tokens are
+    /// already converted, so they are claimed as-is alongside the bootstrap's proceeds.
+    ///
+    /// Returns the total amount of backstop tokens deposited by `from` in this bootstrap
     ///
     /// ### Arguments
+    /// * `from` - The address of the user joining the bootstrap
+    /// * `id` - The bootstrap id to join
+    /// * `amount` - The amount of backstop tokens to join with
+    ///
+    /// ### Panics
+    /// * `BootstrapperDepositError` - If `from` is the bootstrapper
+    pub fn join_lp(e: Env, from: Address, id: u32, amount: i128) -> i128 {
+        from.require_auth();
+        assert_with_error!(
+            e,
+            amount >= 0,
+            BackstopBootstrapperError::NegativeAmountError
+        );
+        let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.update_status(&e);
+        assert_with_error!(
+            e,
+            bootstrap.data.status == BootstrapStatus::Active,
+            BackstopBootstrapperError::InvalidBootstrapStatus
+        );
+        assert_with_error!(
+            e,
+            from != bootstrap.config.bootstrapper,
+            BackstopBootstrapperError::BootstrapperDepositError
+        );
+
+        TokenClient::new(&e, &storage::get_backstop_token(&e)).transfer(
+            &from,
+            &e.current_contract_address(),
+            &amount,
+        );
+        bootstrap.data.backstop_token_deposits += amount;
+        bootstrap.data.total_backstop_tokens += amount;
+        if storage::add_user_id(&e, &from, id) {
+            bootstrap.data.depositors += 1;
+        }
+        bootstrap.store(&e);
+        let mut deposit_data = storage::get_deposit(&e, id, &from);
+        deposit_data.backstop_tokens += amount;
+        storage::set_deposit(&e, id, &from, deposit_data.clone());
+
+        e.events().publish(
+            (Symbol::new(&e, "join_lp"), from, id),
+            (
+                amount,
+                deposit_data.clone(),
+                bootstrap.data.backstop_token_deposits,
+            ),
+        );
+        deposit_data.backstop_tokens
+    }
+
+    /// Exits a bootstrap by withdrawing a given amount of directly deposited backstop tokens
+    ///
+    /// Returns the remaining amount of backstop tokens deposited by `from` in this bootstrap
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user exiting the bootstrap
+    /// * `id` - The bootstrap id to exit
+    /// * `amount` - The amount of backstop tokens to exit with
+    pub fn exit_lp(e: Env, from: Address, id: u32, amount: i128) -> i128 {
+        from.require_auth();
+        assert_with_error!(
+            e,
+            amount >= 0,
+            BackstopBootstrapperError::NegativeAmountError
+        );
+        let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.update_status(&e);
+        assert_with_error!(
+            e,
+            bootstrap.data.status == BootstrapStatus::Active,
+            BackstopBootstrapperError::InvalidBootstrapStatus
+        );
+
+        let mut deposit_data = storage::get_deposit(&e, id, &from);
+        deposit_data.backstop_tokens -= amount;
+        assert_with_error!(
+            e,
+            deposit_data.backstop_tokens >= 0,
+            BackstopBootstrapperError::InsufficientDepositError
+        );
+        bootstrap.data.backstop_token_deposits -= amount;
+        bootstrap.data.total_backstop_tokens -= amount;
+        TokenClient::new(&e, &storage::get_backstop_token(&e)).transfer(
+            &e.current_contract_address(),
+            &from,
+            &amount,
+        );
+        if deposit_data.is_empty() && storage::remove_user_id(&e, &from, id) {
+            bootstrap.data.depositors -= 1;
+        }
+        bootstrap.store(&e);
+        storage::set_deposit(&e, id, &from, deposit_data.clone());
+
+        e.events().publish(
+            (Symbol::new(&e, "exit_lp"), from, id),
+            (
+                amount,
+                deposit_data.clone(),
+                bootstrap.data.backstop_token_deposits,
+            ),
+        );
+        deposit_data.backstop_tokens
+    }
+
+    /// Close the bootstrap by depositing bootstrapping tokens into the comet. The caller is paid
+    /// the bootstrap's keeper fee, if any, out of the backstop tokens minted.
+    ///
+    /// Returns the total amount of backstop tokens held for the bootstrap
+    ///
+    /// ### Arguments
+    /// * `from` - The address closing the bootstrap
     /// * `id` - The id of the bootstrap
-    pub fn close(e: Env, id: u32) -> i128 {
+    ///
+    /// ### Panics
+    /// * `InvalidBootstrapStatus` - If the bootstrap is not closing
+    /// * `UnauthorizedError` - If called during the keeper window by someone other than the
+    ///                         bootstrapper or a keeper
+    pub fn close(e: Env, from: Address, id: u32) -> i128 {
         let mut bootstrap = Bootstrap::load(&e, id);
+        bootstrap.update_status(&e);
         assert_with_error!(
             e,
-            bootstrap.status == BootstrapStatus::Closing,
+            bootstrap.data.status == BootstrapStatus::Closing,
             BackstopBootstrapperError::InvalidBootstrapStatus
         );
+        if e.ledger().sequence() < bootstrap.config.close_ledger + bootstrap.config.keeper_window {
+            from.require_auth();
+            assert_with_error!(
+                e,
+                from == bootstrap.config.bootstrapper || storage::get_is_keeper(&e, &from),
+                BackstopBootstrapperError::UnauthorizedError
+            );
+        }
 
         let comet_client = CometClient::new(&e, &storage::get_backstop_token(&e));
-        let comet_tokens = storage::get_comet_token_data(&e);
-        let bootstrap_info = comet_tokens.get_unchecked(bootstrap.config.token_index);
-        let pair_info = comet_tokens.get_unchecked(bootstrap.config.token_index ^ 1);
-        let bootstrap_token_client = TokenClient::new(&e, &bootstrap_info.address);
-        let pair_token_client = TokenClient::new(&e, &pair_info.address);
+        let comet_tokens = comet_utils::sync_token_data(&e);
+        let mut weights: Vec<i128> = Vec::new(&e);
+        for token in comet_tokens.iter() {
+            weights.push_back(token.weight);
+        }
+        bootstrap.data.weights = weights;
 
-        // get contract starting balances
-        let bootstrap_token_balance = bootstrap_token_client.balance(&e.current_contract_address());
-        let pair_token_balance = pair_token_client.balance(&e.current_contract_address());
+        // verify the comet spot price has not been pushed away from its time-weighted average.
+        // This is checked on every close, so a close following a large single sided deposit
+        // waits for the comet to be rebalanced. The spot price is recorded first, which only
+        // affects the average from this ledger on.
+        let twap = bootstrap.twap(&e);
+        let spot_price = record_spot_price(&e, &mut bootstrap, &comet_client, &comet_tokens);
+        if bootstrap.config.max_price_deviation > 0 {
+            let max_delta = twap
+                .fixed_mul_floor(bootstrap.config.max_price_deviation, SCALAR_7)
+                .unwrap_optimized();
+            assert_with_error!(
+                e,
+                (spot_price - twap).abs() <= max_delta,
+                BackstopBootstrapperError::PriceDeviationError
+            );
+        }
+
+        let keeper_fees = bootstrap.data.keeper_fees;
+        let params = storage::get_params(&e);
 
         // Get Comet LP token underlying value
         let total_comet_shares = comet_client.get_total_supply();
-        let mut comet_bootstrap_token = bootstrap_token_client.balance(&comet_client.address);
-        let mut comet_pair_token = pair_token_client.balance(&comet_client.address);
+        let mut comet_bals: Vec<i128> = Vec::new(&e);
+        let mut all_held = true;
+        for (index, token) in comet_tokens.iter().enumerate() {
+            comet_bals
+                .push_back(TokenClient::new(&e, &token.address).balance(&comet_client.address));
+            all_held &= bootstrap.amount_held(index as u32) > bootstrap.data.max_dust_amount;
+        }
 
-        if bootstrap.data.bootstrap_amount > MAX_DUST_AMOUNT
-            && bootstrap.data.pair_amount > MAX_DUST_AMOUNT
-        {
-            let (dep_bootstrap, dep_pair, minted_backstop) = comet_utils::join_pool(
+        if all_held {
+            let (deposited, minted_backstop) = comet_utils::join_pool(
                 &e,
                 &comet_client,
                 &comet_tokens,
                 &bootstrap,
-                bootstrap_token_balance,
-                pair_token_balance,
-                comet_bootstrap_token,
-                comet_pair_token,
+                &comet_bals,
                 total_comet_shares,
+                &params,
             );
-            bootstrap.convert(dep_bootstrap, dep_pair, minted_backstop);
-            comet_bootstrap_token += dep_bootstrap;
-            comet_pair_token += dep_pair;
+            bootstrap.convert(&deposited, minted_backstop);
+            for (index, amount) in deposited.iter().enumerate() {
+                let index = index as u32;
+                comet_bals.set(index, comet_bals.get_unchecked(index) + amount);
+            }
         }
 
-        // handle single sided bootstrap token deposit
-        if bootstrap.data.bootstrap_amount > MAX_DUST_AMOUNT {
-            let (dep_bootstrap, minted_backstop) = comet_utils::single_sided_join(
-                &e,
-                &comet_client,
-                &bootstrap_info.address,
-                bootstrap.data.bootstrap_amount,
-                comet_bootstrap_token,
-            );
-            bootstrap.convert(dep_bootstrap, 0, minted_backstop);
-        }
-
-        if bootstrap.data.pair_amount > 0 {
-            let (dep_pair, minted_backstop) = comet_utils::single_sided_join(
-                &e,
-                &comet_client,
-                &pair_info.address,
-                bootstrap.data.pair_amount,
-                comet_pair_token,
-            );
-            bootstrap.convert(0, dep_pair, minted_backstop);
+        // handle single sided deposits of the remaining tokens, starting with the bootstrap token
+        for index in bootstrap.close_order().iter() {
+            let amount = bootstrap.single_sided_amount(index);
+            if amount > 0 {
+                let (deposited, minted_backstop) = comet_utils::single_sided_join(
+                    &e,
+                    &comet_client,
+                    &comet_tokens.get_unchecked(index),
+                    amount,
+                    comet_bals.get_unchecked(index),
+                    bootstrap.config.max_slippage,
+                    params.max_in_ratio,
+                );
+                bootstrap.convert_single(index, deposited, minted_backstop);
+            }
         }
 
         assert_with_error!(
             e,
-            bootstrap.data.total_backstop_tokens > 0,
+            bootstrap.data.total_backstop_tokens > bootstrap.data.backstop_token_deposits,
             BackstopBootstrapperError::ReceivedNoBackstopTokens
         );
+        if bootstrap.data.is_converted()
+            && bootstrap.data.total_backstop_tokens >= bootstrap.data.max_dust_amount
+        {
+            bootstrap.set_status(&e, BootstrapStatus::Completed);
+        }
         bootstrap.store(&e);
+
+        // pay the keeper fee for this close to the caller
+        let keeper_fee = bootstrap.data.keeper_fees - keeper_fees;
+        if keeper_fee > 0 {
+            TokenClient::new(&e, &comet_client.address).transfer(
+                &e.current_contract_address(),
+                &from,
+                &keeper_fee,
+            );
+        }
         e.events().publish(
             (Symbol::new(&e, "bootstrap_close"), bootstrap.id),
             bootstrap.data.total_backstop_tokens,
@@ -307,7 +1268,8 @@ impl BackstopBootstrapper {
         bootstrap.data.total_backstop_tokens
     }
 
-    /// Claim and deposit pool tokens into backstop
+    /// Claim and deposit pool tokens into backstop. If the bootstrap has a lock period, only the
+    /// vested backstop tokens are deposited and the rest are released later with `release`.
     ///
     /// Returns the amount of backstop shares minted
     ///
@@ -316,70 +1278,69 @@ impl BackstopBootstrapper {
     /// * `id` - The address of the bootstrap initiator
     pub fn claim(e: Env, from: Address, id: u32) -> i128 {
         from.require_auth();
-        let bootstrap = Bootstrap::load(&e, id);
-        assert_with_error!(
-            e,
-            bootstrap.status == BootstrapStatus::Completed
-                || bootstrap.status == BootstrapStatus::Cancelled,
-            BackstopBootstrapperError::InvalidBootstrapStatus
-        );
-        let backstop_address = storage::get_backstop(&e);
-        let backstop_token_address = storage::get_backstop_token(&e);
-        let backstop_client = backstop::Client::new(&e, &backstop_address);
-        let backstop_token_client = CometClient::new(&e, &backstop_token_address);
-        let backstop_tokens: i128;
-        if bootstrap.config.bootstrapper == from {
-            assert_with_error!(
-                e,
-                !storage::get_claimed(&e, bootstrap.id),
-                BackstopBootstrapperError::AlreadyClaimedError
-            );
-            let bootstrap_info =
-                storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index);
-            backstop_tokens = bootstrap
-                .data
-                .total_backstop_tokens
-                .fixed_mul_floor(bootstrap_info.weight as i128, SCALAR_7)
-                .unwrap_optimized();
-            storage::set_claimed(&e, id);
-        } else {
-            let mut deposit_data = storage::get_deposit(&e, bootstrap.id, &from);
-            assert_with_error!(
-                e,
-                !deposit_data.claimed,
-                BackstopBootstrapperError::AlreadyClaimedError
-            );
-            deposit_data.claimed = true;
-            storage::set_deposit(&e, bootstrap.id, &from, deposit_data.clone());
-            let pair_info =
-                storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index ^ 1);
-            backstop_tokens = deposit_data
-                .amount
-                .fixed_div_floor(bootstrap.data.total_pair, SCALAR_7)
-                .unwrap_optimized()
-                .fixed_mul_floor(bootstrap.data.total_backstop_tokens, SCALAR_7)
-                .unwrap_optimized()
-                .fixed_mul_floor(pair_info.weight as i128, SCALAR_7)
-                .unwrap_optimized();
+        execute_claim(&e, &from, id, &ClaimDestination::Backstop)
+    }
+
+    /// Claim pool tokens and send them to a chosen destination. Claims that vest must be
+    /// deposited into the bootstrap's pool backstop.
+    ///
+    /// Returns the amount of backstop shares minted, or the amount of backstop tokens
+    /// transferred if claimed to the wallet
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user claiming their bootstrap proceeds
+    /// * `id` - The id of the bootstrap
+    /// * `destination` - Where to send the claimed backstop tokens
+    ///
+    /// ### Panics
+    /// * `InvalidPoolAddressError` - If the destination is a pool not deployed by the pool factory
+    /// * `InvalidClaimDestination` - If the claim vests and the destination is not the backstop
+    pub fn claim_to(e: Env, from: Address, id: u32, destination: ClaimDestination) -> i128 {
+        from.require_auth();
+        execute_claim(&e, &from, id, &destination)
+    }
+
+    /// Release the vested backstop tokens of a bootstrap with a lock period by depositing them
+    /// into the bootstrap's pool backstop for `from`
+    ///
+    /// Returns the amount of backstop shares minted
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user releasing their backstop tokens
+    /// * `id` - The id of the bootstrap
+    ///
+    /// ### Panics
+    /// * `NoVestingError` - If `from` has no vesting backstop tokens for the bootstrap
+    pub fn release(e: Env, from: Address, id: u32) -> i128 {
+        from.require_auth();
+        let vesting = match storage::get_vesting(&e, id, &from) {
+            Some(vesting) => vesting,
+            None => panic_with_error!(&e, BackstopBootstrapperError::NoVestingError),
         };
-        backstop_token_client.transfer(&e.current_contract_address(), &from, &backstop_tokens);
-        e.authorize_as_current_contract(vec![
-            &e,
-            InvokerContractAuthEntry::Contract(SubContractInvocation {
-                context: ContractContext {
-                    contract: backstop_token_address,
-                    fn_name: Symbol::new(&e, "transfer"),
-                    args: vec![
-                        &e,
-                        from.into_val(&e),
-                        backstop_address.into_val(&e),
-                        backstop_tokens.into_val(&e),
-                    ],
-                },
-                sub_invocations: Vec::new(&e),
-            }),
-        ]);
-        backstop_client.deposit(&from, &bootstrap.config.pool, &backstop_tokens)
+        let mut bootstrap = Bootstrap::load(&e, id);
+        let (released, minted) = release_vested(&e, &from, &mut bootstrap, vesting);
+        bootstrap.store(&e);
+        e.events()
+            .publish((Symbol::new(&e, "release"), from, id), (released, minted));
+        minted
+    }
+
+    /// Claim and deposit pool tokens into backstop for multiple bootstraps
+    ///
+    /// Returns the amount of backstop shares minted for each bootstrap
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user claiming their bootstrap proceeds
+    /// * `ids` - The ids of the bootstraps to claim
+    pub fn claim_all(e: Env, from: Address, ids: Vec<u32>) -> Vec<i128> {
+        from.require_auth();
+        let mut amounts: Vec<i128> = Vec::new(&e);
+        for id in ids.iter() {
+            amounts.push_back(execute_claim(&e, &from, id, &ClaimDestination::Backstop));
+        }
+        e.events()
+            .publish((Symbol::new(&e, "claim_all"), from), (ids, amounts.clone()));
+        amounts
     }
 
     /// Refund funds from a cancelled bootstrap
@@ -391,57 +1352,350 @@ impl BackstopBootstrapper {
     /// * `id` - The address of the bootstrap initiator
     pub fn refund(e: Env, from: Address, id: u32) -> i128 {
         from.require_auth();
-        let bootstrap = Bootstrap::load(&e, id);
+        execute_refund(&e, &from, id)
+    }
+
+    /// Refund funds from multiple cancelled bootstraps
+    ///
+    /// Returns the amount of funds returned for each bootstrap
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user claiming their bootstrap proceeds
+    /// * `ids` - The ids of the bootstraps to refund
+    pub fn refund_all(e: Env, from: Address, ids: Vec<u32>) -> Vec<i128> {
+        from.require_auth();
+        let mut amounts: Vec<i128> = Vec::new(&e);
+        for id in ids.iter() {
+            amounts.push_back(execute_refund(&e, &from, id));
+        }
+        e.events().publish(
+            (Symbol::new(&e, "refund_all"), from),
+            (ids, amounts.clone()),
+        );
+        amounts
+    }
+}
+
+/// Verify the protocol parameters are within their bounds
+///
+/// ### Arguments
+/// * `params` - The protocol parameters
+///
+/// ### Panics
+/// * `InvalidProtocolParams` - If the protocol parameters are out of bounds
+fn require_valid_params(e: &Env, params: &ProtocolParams) {
+    let one_day = params.one_day_ledgers;
+    assert_with_error!(
+        e,
+        (storage::ONE_DAY_LEDGERS / 4..=storage::ONE_DAY_LEDGERS * 4).contains(&one_day)
+            && params.min_duration > 0
+            && params.min_duration <= params.max_duration
+            && params.max_duration <= 30 * one_day
+            && (one_day..=30 * one_day).contains(&params.close_expiry)
+            && params.max_dust_amount > 0
+            && params.max_dust_amount <= SCALAR_7
+            && params.max_in_ratio > 0
+            && params.max_in_ratio <= SCALAR_7 / 2
+            && (0_9900000..SCALAR_7).contains(&params.join_buffer),
+        BackstopBootstrapperError::InvalidProtocolParams
+    );
+}
+
+/// Claim the backstop tokens owed to `from` by a bootstrap and send them to `destination`. If
+/// the bootstrap is partially completed, `from`'s share of the unconverted tokens is refunded
+/// to them as well. If the claim vests, the contract holds the backstop tokens and deposits
+/// them into the backstop for `from` as they vest.
+///
+/// Returns the amount of backstop shares minted, or the amount of backstop tokens transferred
+/// if claimed to the wallet
+///
+/// ### Arguments
+/// * `from` - The address of the user claiming their bootstrap proceeds
+/// * `id` - The id of the bootstrap
+/// * `destination` - Where to send the claimed backstop tokens
+///
+/// ### Panics
+/// * `InvalidClaimDestination` - If the claim vests and the destination is not the backstop
+fn execute_claim(e: &Env, from: &Address, id: u32, destination: &ClaimDestination) -> i128 {
+    let mut bootstrap = Bootstrap::load(e, id);
+    bootstrap.update_status(e);
+    assert_with_error!(
+        e,
+        bootstrap.data.status == BootstrapStatus::Completed
+            || bootstrap.data.status == BootstrapStatus::PartiallyCompleted,
+        BackstopBootstrapperError::InvalidBootstrapStatus
+    );
+    if let ClaimDestination::OtherPool(pool) = destination {
         assert_with_error!(
             e,
-            bootstrap.status == BootstrapStatus::Cancelled,
-            BackstopBootstrapperError::InvalidBootstrapStatus
+            pool_factory::Client::new(e, &storage::get_pool_factory(e)).is_pool(pool),
+            BackstopBootstrapperError::InvalidPoolAddressError
         );
-        let amount_refunded: i128;
-        if bootstrap.config.bootstrapper == from {
-            assert_with_error!(
-                e,
-                !storage::get_refunded(&e, id),
-                BackstopBootstrapperError::AlreadyRefundedError
-            );
-            let bootstrap_info =
-                storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index);
-            amount_refunded = bootstrap.data.bootstrap_amount;
-            TokenClient::new(&e, &bootstrap_info.address).transfer(
-                &e.current_contract_address(),
-                &from,
-                &amount_refunded,
-            );
-            storage::set_refunded(&e, id);
-        } else {
-            let mut deposit_data = storage::get_deposit(&e, bootstrap.id, &from);
-            assert_with_error!(
-                e,
-                !deposit_data.refunded,
-                BackstopBootstrapperError::AlreadyRefundedError
-            );
-            let deposit_amount = deposit_data.amount;
-            deposit_data.refunded = true;
-            storage::set_deposit(&e, bootstrap.id, &from, deposit_data);
-
-            amount_refunded = deposit_amount
-                .fixed_mul_floor(
-                    bootstrap
-                        .data
-                        .pair_amount
-                        .fixed_div_floor(bootstrap.data.total_pair, SCALAR_7)
-                        .unwrap_optimized(),
-                    SCALAR_7,
-                )
-                .unwrap_optimized();
-            let pair_info =
-                storage::get_comet_token_data(&e).get_unchecked(bootstrap.config.token_index ^ 1);
-            TokenClient::new(&e, &pair_info.address).transfer(
+    }
+    let backstop_tokens: i128;
+    let refundable: Vec<i128>;
+    if bootstrap.config.bootstrapper == *from {
+        assert_with_error!(
+            e,
+            !storage::get_claimed(e, bootstrap.id),
+            BackstopBootstrapperError::AlreadyClaimedError
+        );
+        backstop_tokens = bootstrap.data.bootstrap_backstop_tokens;
+        refundable = bootstrap.bootstrapper_refundable(e);
+        storage::set_claimed(e, id);
+    } else {
+        let mut deposit_data = storage::get_deposit(e, bootstrap.id, from);
+        assert_with_error!(
+            e,
+            !deposit_data.claimed,
+            BackstopBootstrapperError::AlreadyClaimedError
+        );
+        deposit_data.claimed = true;
+        storage::set_deposit(e, bootstrap.id, from, deposit_data.clone());
+        backstop_tokens = bootstrap.claimable(&deposit_data);
+        refundable = bootstrap.refundable(e, &deposit_data);
+        if storage::remove_user_id(e, from, id) {
+            bootstrap.data.depositors -= 1;
+        }
+    };
+    bootstrap.data.claimed_backstop_tokens += backstop_tokens;
+    if bootstrap.data.status == BootstrapStatus::PartiallyCompleted {
+        send_refund(e, from, &mut bootstrap, refundable, 0);
+    }
+    let close_ledger = bootstrap.config.close_ledger;
+    let (cliff_ledgers, vesting_ledgers) = if bootstrap.config.bootstrapper == *from
+        && bootstrap.config.bootstrapper_vesting_ledgers > 0
+    {
+        (
+            bootstrap.config.bootstrapper_cliff_ledgers,
+            bootstrap.config.bootstrapper_vesting_ledgers,
+        )
+    } else {
+        (0, bootstrap.config.lock_ledgers)
+    };
+    let (released, minted) = if vesting_ledgers > 0 {
+        assert_with_error!(
+            e,
+            matches!(destination, ClaimDestination::Backstop),
+            BackstopBootstrapperError::InvalidClaimDestination
+        );
+        let vesting = VestingData {
+            amount: backstop_tokens,
+            released: 0,
+            start_ledger: close_ledger,
+            cliff_ledger: close_ledger + cliff_ledgers,
+            end_ledger: close_ledger + vesting_ledgers,
+        };
+        release_vested(e, from, &mut bootstrap, vesting)
+    } else {
+        bootstrap.data.paid_backstop_tokens += backstop_tokens;
+        (
+            backstop_tokens,
+            send_backstop_tokens(e, from, &bootstrap, destination, backstop_tokens),
+        )
+    };
+    bootstrap.store(e);
+    e.events().publish(
+        (Symbol::new(e, "claim"), from.clone(), id),
+        (destination.clone(), backstop_tokens, released, minted),
+    );
+    minted
+}
+
+/// Record the current comet spot price of the bootstrap token into the bootstrap's
+/// time-weighted average price. The bootstrap must be stored for the price to persist.
+///
+/// Returns the current comet spot price of the bootstrap token, in pair tokens
+///
+/// ### Arguments
+/// * `bootstrap` - The bootstrap to record the price for
+/// * `comet_client` - The comet client
+/// * `comet_tokens` - The comet token data
+fn record_spot_price(
+    e: &Env,
+    bootstrap: &mut Bootstrap,
+    comet_client: &CometClient,
+    comet_tokens: &Vec<TokenInfo>,
+) -> i128 {
+    let spot_price = comet_utils::get_spot_price(
+        comet_client,
+        &comet_tokens
+            .get_unchecked(bootstrap.config.token_index)
+            .address,
+        &comet_tokens.get_unchecked(bootstrap.price_index()).address,
+    );
+    bootstrap.record_price(e, spot_price);
+    spot_price
+}
+
+/// Deposit the currently vested backstop tokens into the bootstrap's pool backstop for `from`
+/// and store the updated vesting data. The bootstrap must be stored for its paid backstop tokens
+/// to persist.
+///
+/// Returns the amount of backstop tokens released, and the amount of backstop shares minted
+///
+/// ### Arguments
+/// * `from` - The address of the user releasing their backstop tokens
+/// * `bootstrap` - The bootstrap the backstop tokens were claimed from
+/// * `vesting` - The vesting data of the user
+fn release_vested(
+    e: &Env,
+    from: &Address,
+    bootstrap: &mut Bootstrap,
+    mut vesting: VestingData,
+) -> (i128, i128) {
+    let to_release = vesting.vested(e.ledger().sequence()) - vesting.released;
+    vesting.released += to_release;
+    storage::set_vesting(e, bootstrap.id, from, &vesting);
+    if to_release > 0 {
+        bootstrap.data.paid_backstop_tokens += to_release;
+        (
+            to_release,
+            send_backstop_tokens(e, from, bootstrap, &ClaimDestination::Backstop, to_release),
+        )
+    } else {
+        (0, 0)
+    }
+}
+
+/// Send backstop tokens held by the contract to `from`'s claim destination
+///
+/// Returns the amount of backstop shares minted, or the amount of backstop tokens transferred
+/// if sent to the wallet
+///
+/// ### Arguments
+/// * `from` - The address of the user receiving the backstop tokens
+/// * `bootstrap` - The bootstrap the backstop tokens were claimed from
+/// * `destination` - Where to send the backstop tokens
+/// * `backstop_tokens` - The amount of backstop tokens to send
+fn send_backstop_tokens(
+    e: &Env,
+    from: &Address,
+    bootstrap: &Bootstrap,
+    destination: &ClaimDestination,
+    backstop_tokens: i128,
+) -> i128 {
+    let backstop_address = storage::get_backstop(e);
+    let backstop_token_address = storage::get_backstop_token(e);
+    let backstop_client = backstop::Client::new(e, &backstop_address);
+    let backstop_token_client = CometClient::new(e, &backstop_token_address);
+    backstop_token_client.transfer(&e.current_contract_address(), from, &backstop_tokens);
+    let pool = match destination {
+        ClaimDestination::Backstop => bootstrap.config.pool.clone(),
+        ClaimDestination::Wallet => return backstop_tokens,
+        ClaimDestination::OtherPool(pool) => pool.clone(),
+    };
+    e.authorize_as_current_contract(vec![
+        e,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: backstop_token_address,
+                fn_name: Symbol::new(e, "transfer"),
+                args: vec![
+                    e,
+                    from.into_val(e),
+                    backstop_address.into_val(e),
+                    backstop_tokens.into_val(e),
+                ],
+            },
+            sub_invocations: Vec::new(e),
+        }),
+    ]);
+    backstop_client.deposit(from, &pool, &backstop_tokens)
+}
+
+/// Refund the tokens owed to `from` by a cancelled bootstrap
+///
+/// Returns the amount of funds returned, summed across deposited tokens for depositors
+///
+/// ### Arguments
+/// * `from` - The address of the user claiming their bootstrap refund
+/// * `id` - The id of the bootstrap
+fn execute_refund(e: &Env, from: &Address, id: u32) -> i128 {
+    let mut bootstrap = Bootstrap::load(e, id);
+    bootstrap.update_status(e);
+    assert_with_error!(
+        e,
+        bootstrap.data.status == BootstrapStatus::Cancelled,
+        BackstopBootstrapperError::InvalidBootstrapStatus
+    );
+    let amount_refunded = if bootstrap.config.bootstrapper == *from {
+        assert_with_error!(
+            e,
+            !storage::get_refunded(e, id),
+            BackstopBootstrapperError::AlreadyRefundedError
+        );
+        storage::set_refunded(e, id);
+        let refundable = bootstrap.bootstrapper_refundable(e);
+        send_refund(e, from, &mut bootstrap, refundable, 0)
+    } else {
+        let mut deposit_data = storage::get_deposit(e, bootstrap.id, from);
+        assert_with_error!(
+            e,
+            !deposit_data.refunded,
+            BackstopBootstrapperError::AlreadyRefundedError
+        );
+        deposit_data.refunded = true;
+        storage::set_deposit(e, bootstrap.id, from, deposit_data.clone());
+        if storage::remove_user_id(e, from, id) {
+            bootstrap.data.depositors -= 1;
+        }
+        bootstrap.data.claimed_backstop_tokens += deposit_data.backstop_tokens;
+        let refundable = bootstrap.refundable(e, &deposit_data);
+        send_refund(
+            e,
+            from,
+            &mut bootstrap,
+            refundable,
+            deposit_data.backstop_tokens,
+        )
+    };
+    bootstrap.store(e);
+    amount_refunded
+}
+
+/// Send refunded comet tokens and backstop tokens held by the contract to `from`. The bootstrap
+/// must be stored for its paid amounts to persist.
+///
+/// Returns the amount of funds returned, summed across tokens
+///
+/// ### Arguments
+/// * `from` - The address of the user receiving the refund
+/// * `bootstrap` - The bootstrap the tokens are refunded from
+/// * `amounts` - The amount of each comet token to refund, indexed by comet token index
+/// * `backstop_tokens` - The amount of backstop tokens to refund
+fn send_refund(
+    e: &Env,
+    from: &Address,
+    bootstrap: &mut Bootstrap,
+    amounts: Vec<i128>,
+    backstop_tokens: i128,
+) -> i128 {
+    let comet_tokens = storage::get_comet_token_data(e);
+    for (index, amount) in amounts.iter().enumerate() {
+        if amount > 0 {
+            let index = index as u32;
+            let paid = bootstrap.data.paid_amounts.get_unchecked(index);
+            bootstrap.data.paid_amounts.set(index, paid + amount);
+            TokenClient::new(e, &comet_tokens.get_unchecked(index).address).transfer(
                 &e.current_contract_address(),
-                &from,
-                &amount_refunded,
+                from,
+                &amount,
             );
         }
-        amount_refunded
     }
+    if backstop_tokens > 0 {
+        bootstrap.data.paid_backstop_tokens += backstop_tokens;
+        TokenClient::new(e, &storage::get_backstop_token(e)).transfer(
+            &e.current_contract_address(),
+            from,
+            &backstop_tokens,
+        );
+    }
+    let amount_refunded = amounts.iter().sum::<i128>() + backstop_tokens;
+    e.events().publish(
+        (Symbol::new(e, "refund"), from.clone(), bootstrap.id),
+        (amounts, backstop_tokens),
+    );
+    amount_refunded
 }
@@ -26,4 +26,22 @@ pub enum BackstopBootstrapperError {
     InsufficientDepositError = 106,
     ReceivedNoBackstopTokens = 107,
     AlreadyRefundedError = 108,
+    InvalidBackstopTokenError = 109,
+    InvalidPairMin = 110,
+    InvalidPairMax = 111,
+    PairMaxExceededError = 112,
+    InvalidMaxSlippage = 113,
+    SlippageExceededError = 114,
+    InvalidMaxPriceDeviation = 115,
+    PriceDeviationError = 116,
+    InvalidKeeperWindow = 117,
+    InvalidKeeperFee = 118,
+    InvalidLockLedgers = 119,
+    NoVestingError = 120,
+    InvalidBootstrapperVesting = 121,
+    InvalidPairToken = 122,
+    InvalidProtocolParams = 123,
+    BackstopTokensHeldError = 124,
+    InvalidClaimDestination = 125,
+    BootstrapperDepositError = 126,
 }
@@ -1,15 +1,19 @@
 use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env, Symbol, Vec};
 
-use crate::types::{BootstrapConfig, BootstrapData, DepositData, TokenInfo};
+use crate::types::{
+    BootstrapConfig, BootstrapData, DepositData, ProtocolParams, TokenInfo, VestingData,
+};
 
 //********** Storage Keys **********//
 
+const ADMIN_KEY: &str = "Admin";
 const BACKSTOP_KEY: &str = "Bstop";
 const POOL_FACTORY_KEY: &str = "PoolFact";
 const BACKSTOP_TOKEN_KEY: &str = "BstopTkn";
 const COMET_KEY: &str = "Comet";
 const IS_INIT_KEY: &str = "IsInit";
 const NEXT_ID_KEY: &str = "NextId";
+const PARAMS_KEY: &str = "Params";
 
 #[derive(Clone)]
 #[contracttype]
@@ -26,6 +30,16 @@ pub enum BootstrapKey {
     Claim(u32),
     Refund(u32),
     Deposit(DepositKey),
+    Vesting(DepositKey),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Keeper(Address),
+    PoolIds(Address),
+    BootstrapperIds(Address),
+    UserIds(Address),
 }
 
 //********** Storage Utils **********//
@@ -59,6 +73,21 @@ pub fn set_is_init(e: &Env) {
         .set::<Symbol, bool>(&Symbol::new(e, IS_INIT_KEY), &true);
 }
 
+/// Get the admin address
+pub fn get_admin(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get::<Symbol, Address>(&Symbol::new(e, ADMIN_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the admin address
+pub fn set_admin(e: &Env, admin: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, ADMIN_KEY), admin);
+}
+
 /// Get the backstop address
 pub fn get_backstop(e: &Env) -> Address {
     e.storage()
@@ -104,6 +133,22 @@ pub fn set_backstop_token(e: &Env, backstop_token: Address) {
         .set::<Symbol, Address>(&Symbol::new(e, BACKSTOP_TOKEN_KEY), &backstop_token);
 }
 
+/// Get the protocol parameters. Contracts initialized before the parameters were stored use
+/// the defaults.
+pub fn get_params(e: &Env) -> ProtocolParams {
+    e.storage()
+        .instance()
+        .get::<Symbol, ProtocolParams>(&Symbol::new(e, PARAMS_KEY))
+        .unwrap_or_default()
+}
+
+/// Set the protocol parameters
+pub fn set_params(e: &Env, params: &ProtocolParams) {
+    e.storage()
+        .instance()
+        .set::<Symbol, ProtocolParams>(&Symbol::new(e, PARAMS_KEY), params);
+}
+
 /// Get comet token data
 pub fn get_comet_token_data(e: &Env) -> Vec<TokenInfo> {
     e.storage()
@@ -142,6 +187,109 @@ pub fn set_next_id(e: &Env, next_id: u32) {
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
+/// Get if an address is an allowlisted keeper
+pub fn get_is_keeper(e: &Env, keeper: &Address) -> bool {
+    let key = DataKey::Keeper(keeper.clone());
+    let result = e.storage().persistent().get::<DataKey, bool>(&key);
+    match result {
+        Some(is_keeper) => {
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+            is_keeper
+        }
+        None => false,
+    }
+}
+
+/// Add or remove an address from the keeper allowlist
+pub fn set_is_keeper(e: &Env, keeper: &Address, is_keeper: bool) {
+    let key = DataKey::Keeper(keeper.clone());
+    if is_keeper {
+        e.storage().persistent().set::<DataKey, bool>(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    } else {
+        e.storage().persistent().remove::<DataKey>(&key);
+    }
+}
+
+/// Get the ids of the bootstraps created for a pool
+pub fn get_pool_ids(e: &Env, pool: &Address) -> Vec<u32> {
+    get_id_index(e, &DataKey::PoolIds(pool.clone()))
+}
+
+/// Set the ids of the bootstraps created for a pool
+pub fn set_pool_ids(e: &Env, pool: &Address, ids: &Vec<u32>) {
+    set_id_index(e, &DataKey::PoolIds(pool.clone()), ids);
+}
+
+/// Get the ids of the bootstraps created by a bootstrapper
+pub fn get_bootstrapper_ids(e: &Env, bootstrapper: &Address) -> Vec<u32> {
+    get_id_index(e, &DataKey::BootstrapperIds(bootstrapper.clone()))
+}
+
+/// Set the ids of the bootstraps created by a bootstrapper
+pub fn set_bootstrapper_ids(e: &Env, bootstrapper: &Address, ids: &Vec<u32>) {
+    set_id_index(e, &DataKey::BootstrapperIds(bootstrapper.clone()), ids);
+}
+
+/// Get the ids of the bootstraps a user has joined and not yet claimed or been refunded from
+pub fn get_user_ids(e: &Env, user: &Address) -> Vec<u32> {
+    get_id_index(e, &DataKey::UserIds(user.clone()))
+}
+
+/// Add a bootstrap to the ids a user has joined, if it is not already included
+///
+/// Returns true if the bootstrap was added
+pub fn add_user_id(e: &Env, user: &Address, id: u32) -> bool {
+    let key = DataKey::UserIds(user.clone());
+    let mut ids = get_id_index(e, &key);
+    if !ids.contains(id) {
+        ids.push_back(id);
+        set_id_index(e, &key, &ids);
+        true
+    } else {
+        false
+    }
+}
+
+/// Remove a bootstrap from the ids a user has joined
+///
+/// Returns true if the bootstrap was removed
+pub fn remove_user_id(e: &Env, user: &Address, id: u32) -> bool {
+    let key = DataKey::UserIds(user.clone());
+    let mut ids = get_id_index(e, &key);
+    if let Some(index) = ids.first_index_of(id) {
+        ids.remove(index);
+        set_id_index(e, &key, &ids);
+        true
+    } else {
+        false
+    }
+}
+
+fn get_id_index(e: &Env, key: &DataKey) -> Vec<u32> {
+    let result = e.storage().persistent().get::<DataKey, Vec<u32>>(key);
+    match result {
+        Some(ids) => {
+            e.storage()
+                .persistent()
+                .extend_ttl(key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+            ids
+        }
+        None => Vec::new(e),
+    }
+}
+
+fn set_id_index(e: &Env, key: &DataKey, ids: &Vec<u32>) {
+    e.storage().persistent().set::<DataKey, Vec<u32>>(key, ids);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
 /// Get a bootstrap
 pub fn get_bootstrap_config(e: &Env, id: u32) -> BootstrapConfig {
     let key = BootstrapKey::Config(id);
@@ -205,7 +353,7 @@ pub fn get_deposit(e: &Env, id: u32, user: &Address) -> DepositData {
                 .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
             data
         }
-        None => DepositData::default(),
+        None => DepositData::new(e),
     }
 }
 
@@ -223,6 +371,38 @@ pub fn set_deposit(e: &Env, id: u32, user: &Address, data: DepositData) {
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
+/// Get the vesting backstop tokens of a user for a bootstrap
+pub fn get_vesting(e: &Env, id: u32, user: &Address) -> Option<VestingData> {
+    let key = BootstrapKey::Vesting(DepositKey {
+        id,
+        user: user.clone(),
+    });
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BootstrapKey, VestingData>(&key);
+    if result.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+    }
+    result
+}
+
+/// Set the vesting backstop tokens of a user for a bootstrap
+pub fn set_vesting(e: &Env, id: u32, user: &Address, data: &VestingData) {
+    let key = BootstrapKey::Vesting(DepositKey {
+        id,
+        user: user.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<BootstrapKey, VestingData>(&key, data);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
 /// Get if the bootstrapper claimed their backstop token balance
 pub fn get_claimed(e: &Env, id: u32) -> bool {
     let key = BootstrapKey::Claim(id);
@@ -1,7 +1,17 @@
+mod test_admin;
+mod test_cancel;
 mod test_claim;
 mod test_close;
 mod test_create_bootstrap;
+mod test_dust;
 mod test_frontrun;
 mod test_happy_path;
 mod test_join_exit;
+mod test_list_bootstraps;
+mod test_multi_token;
+mod test_partial_close;
+mod test_poke;
+mod test_quote;
 mod test_refund;
+mod test_update_bootstrap;
+mod test_user_positions;
@@ -0,0 +1,483 @@
+#![cfg(test)]
+
+use crate::constants::{MAX_DUST_AMOUNT, SCALAR_7};
+use crate::dependencies::comet;
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{assert_approx_eq_abs, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus, ProtocolParams};
+use crate::{BackstopBootstrapper, BackstopBootstrapperClient};
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{
+    Address as _, AuthorizedFunction, AuthorizedInvocation, BytesN as _, Events,
+};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
+
+#[test]
+fn test_set_admin() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let gandalf = Address::generate(&e);
+    let saruman = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+
+    let bootstrapper = e.register_contract(None, BackstopBootstrapper {});
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+    bootstrap_client.initialize(
+        &gandalf,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+    assert_eq!(bootstrap_client.get_admin(), gandalf);
+
+    let result = bootstrap_client.try_initialize(
+        &saruman,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(3))));
+
+    bootstrap_client.set_admin(&saruman);
+    assert_eq!(
+        e.auths(),
+        std::vec![
+            (
+                gandalf.clone(),
+                AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        bootstrapper.clone(),
+                        Symbol::new(&e, "set_admin"),
+                        vec![&e, saruman.into_val(&e)],
+                    )),
+                    sub_invocations: std::vec![],
+                }
+            ),
+            (
+                saruman.clone(),
+                AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        bootstrapper.clone(),
+                        Symbol::new(&e, "set_admin"),
+                        vec![&e, saruman.into_val(&e)],
+                    )),
+                    sub_invocations: std::vec![],
+                }
+            )
+        ]
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "set_admin"), gandalf.clone()).into_val(&e),
+                saruman.into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(bootstrap_client.get_admin(), saruman);
+}
+
+#[test]
+fn test_upgrade() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let gandalf = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+
+    let bootstrapper = e.register_contract(None, BackstopBootstrapper {});
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+    bootstrap_client.initialize(
+        &gandalf,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+
+    let wasm_hash = e.deployer().upload_contract_wasm(comet::WASM);
+    bootstrap_client.upgrade(&wasm_hash);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            gandalf.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    bootstrapper.clone(),
+                    Symbol::new(&e, "upgrade"),
+                    vec![&e, wasm_hash.into_val(&e)],
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_set_keeper() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let gandalf = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+
+    let bootstrapper = e.register_contract(None, BackstopBootstrapper {});
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+    bootstrap_client.initialize(
+        &gandalf,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+    assert!(!bootstrap_client.is_keeper(&merry));
+
+    bootstrap_client.set_keeper(&merry, &true);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            gandalf.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    bootstrapper.clone(),
+                    Symbol::new(&e, "set_keeper"),
+                    vec![&e, merry.into_val(&e), true.into_val(&e)],
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "set_keeper"), merry.clone()).into_val(&e),
+                true.into_val(&e)
+            )
+        ]
+    );
+    assert!(bootstrap_client.is_keeper(&merry));
+
+    bootstrap_client.set_keeper(&merry, &false);
+    assert!(!bootstrap_client.is_keeper(&merry));
+}
+
+#[test]
+fn test_set_params() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let gandalf = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = e.register_contract(None, BackstopBootstrapper {});
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // initialize validates the params
+    let invalid_params = ProtocolParams {
+        max_in_ratio: SCALAR_7,
+        ..Default::default()
+    };
+    let result = bootstrap_client.try_initialize(
+        &gandalf,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &invalid_params,
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(123))));
+
+    bootstrap_client.initialize(
+        &gandalf,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+    assert!(bootstrap_client.get_params() == ProtocolParams::default());
+
+    let params = ProtocolParams {
+        one_day_ledgers: ONE_DAY_LEDGERS,
+        min_duration: ONE_DAY_LEDGERS / 2,
+        max_duration: 7 * ONE_DAY_LEDGERS,
+        close_expiry: 7 * ONE_DAY_LEDGERS,
+        max_dust_amount: 0_0010000,
+        max_in_ratio: SCALAR_7 / 4,
+        join_buffer: 0_9990000,
+    };
+    bootstrap_client.set_params(&params);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            gandalf.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    bootstrapper.clone(),
+                    Symbol::new(&e, "set_params"),
+                    vec![&e, params.into_val(&e)],
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "set_params"),).into_val(&e),
+                params.into_val(&e)
+            )
+        ]
+    );
+    assert!(bootstrap_client.get_params() == params);
+
+    // new bootstraps use the updated duration bounds
+    blnd_client.mint(&frodo, &(1000 * SCALAR_7));
+    let mut config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + 10 * ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: 1000 * SCALAR_7,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let result = bootstrap_client.try_bootstrap(&config);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(100))));
+    config.close_ledger = e.ledger().sequence() + ONE_DAY_LEDGERS / 2;
+    let id = bootstrap_client.bootstrap(&config);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.data.close_expiry, params.close_expiry);
+    assert_eq!(bootstrap.data.max_dust_amount, params.max_dust_amount);
+    usdc_client.mint(&samwise, &(10 * SCALAR_7));
+    bootstrap_client.join(&samwise, &id, &1, &(10 * SCALAR_7));
+
+    // params outside of their bounds are rejected
+    let mut invalid_params = params.clone();
+    invalid_params.min_duration = 8 * ONE_DAY_LEDGERS;
+    let result = bootstrap_client.try_set_params(&invalid_params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(123))));
+
+    let mut invalid_params = params.clone();
+    invalid_params.close_expiry = 0;
+    let result = bootstrap_client.try_set_params(&invalid_params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(123))));
+
+    let mut invalid_params = params.clone();
+    invalid_params.max_dust_amount = 0;
+    let result = bootstrap_client.try_set_params(&invalid_params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(123))));
+
+    let mut invalid_params = params.clone();
+    invalid_params.join_buffer = SCALAR_7;
+    let result = bootstrap_client.try_set_params(&invalid_params);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(123))));
+
+    // in-flight bootstraps keep the close expiry and dust amount they were created with
+    bootstrap_client.set_params(&ProtocolParams::default());
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.data.close_expiry, params.close_expiry);
+    assert_eq!(bootstrap.data.max_dust_amount, params.max_dust_amount);
+
+    e.jump(ONE_DAY_LEDGERS / 2 + params.close_expiry);
+    assert!(bootstrap_client.poke(&id) == BootstrapStatus::Closing);
+    e.jump(1);
+    assert!(bootstrap_client.poke(&id) == BootstrapStatus::Cancelled);
+}
+
+#[test]
+fn test_migrate_keeps_inflight_bootstraps() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let gandalf = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = e.register_contract(None, BackstopBootstrapper {});
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+    bootstrap_client.initialize(
+        &gandalf,
+        &blend_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+
+    // create and join a bootstrap against the original contracts
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // validate migrations to contracts with different underlying tokens fail
+    let wbtc = e.register_stellar_asset_contract(bombadil.clone());
+    let wbtc_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &wbtc);
+    let result = bootstrap_client.try_migrate(
+        &wbtc_fixture.backstop.address,
+        &wbtc_fixture.backstop_token.address,
+        &wbtc_fixture.pool_factory.address,
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+
+    // validate the backstop token must match the backstop
+    let new_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let result = bootstrap_client.try_migrate(
+        &new_fixture.backstop.address,
+        &blend_fixture.backstop_token.address,
+        &new_fixture.pool_factory.address,
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+
+    // validate the backstop token can't change while backstop tokens are held
+    let lp_amount = 10 * SCALAR_7;
+    blend_fixture
+        .backstop_token
+        .transfer(&bombadil, &samwise, &lp_amount);
+    bootstrap_client.join_lp(&samwise, &id, &lp_amount);
+    let result = bootstrap_client.try_migrate(
+        &new_fixture.backstop.address,
+        &new_fixture.backstop_token.address,
+        &new_fixture.pool_factory.address,
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(124))));
+    bootstrap_client.exit_lp(&samwise, &id, &lp_amount);
+
+    bootstrap_client.migrate(
+        &new_fixture.backstop.address,
+        &new_fixture.backstop_token.address,
+        &new_fixture.pool_factory.address,
+    );
+    assert_eq!(
+        e.auths()[0].0,
+        gandalf,
+        "migrate must be authorized by the admin"
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "migrate"),).into_val(&e),
+                (
+                    new_fixture.backstop.address.clone(),
+                    new_fixture.backstop_token.address.clone(),
+                    new_fixture.pool_factory.address.clone()
+                )
+                    .into_val(&e)
+            )
+        ]
+    );
+
+    // in-flight bootstrap closes into the new backstop token
+    e.jump(ONE_DAY_LEDGERS + 1);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+    assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+    assert_eq!(
+        backstop_tokens,
+        new_fixture.backstop_token.balance(&bootstrapper)
+    );
+    assert_eq!(0, blend_fixture.backstop_token.balance(&bootstrapper));
+}
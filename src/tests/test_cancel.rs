@@ -0,0 +1,222 @@
+#![cfg(test)]
+
+use crate::constants::SCALAR_7;
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{self, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{
+    Address as _, AuthorizedFunction, AuthorizedInvocation, BytesN as _, Events,
+};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
+
+#[test]
+fn test_cancel_and_refund() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + 7 * ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // refund is not possible before cancelling
+    e.jump(ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_refund(&frodo, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    // cancel the bootstrap
+    bootstrap_client.cancel(&id);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            frodo.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    bootstrapper.clone(),
+                    Symbol::new(&e, "cancel"),
+                    vec![&e, id.into_val(&e)],
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "bootstrap_cancel"), frodo.clone(), id).into_val(&e),
+                vec![&e, 0, join_amount].into_val(&e)
+            )
+        ]
+    );
+    let events = e.events().all();
+    let event = vec![&e, events.get_unchecked(events.len() - 2)];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "bootstrap_status"), id).into_val(&e),
+                BootstrapStatus::Cancelled.into_val(&e)
+            )
+        ]
+    );
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Cancelled);
+
+    // bootstrap can no longer be joined, exited, closed or cancelled
+    let result = bootstrap_client.try_join(&samwise, &id, &1, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let result = bootstrap_client.try_exit(&samwise, &id, &1, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let result = bootstrap_client.try_cancel(&id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    e.jump(7 * ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    // cancelled bootstraps can only be refunded
+    let result = bootstrap_client.try_claim(&samwise, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    // both sides are fully refunded
+    let refunded = bootstrap_client.refund(&frodo, &id);
+    assert_eq!(refunded, bootstrap_amount);
+    assert_eq!(bootstrap_amount, blnd_token.balance(&frodo));
+    assert_eq!(0, blnd_token.balance(&bootstrapper));
+
+    let refunded = bootstrap_client.refund(&samwise, &id);
+    assert_eq!(refunded, join_amount);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "refund"), samwise.clone(), id).into_val(&e),
+                (vec![&e, 0, join_amount], 0i128).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(join_amount, usdc_token.balance(&samwise));
+    assert_eq!(0, usdc_token.balance(&bootstrapper));
+}
+
+#[test]
+fn test_cancel_validates_status() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // verify cancel cannot be run once the bootstrap is closing
+    e.jump(ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_cancel(&id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    // verify cancel cannot be run once the bootstrap is completed
+    bootstrap_client.close(&bombadil, &id);
+    let result = bootstrap_client.try_cancel(&id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+}
@@ -1,17 +1,17 @@
 #![cfg(test)]
 
 use crate::constants::{MAX_DUST_AMOUNT, SCALAR_7};
-use crate::storage::ONE_DAY_LEDGERS;
+use crate::storage::{self, ONE_DAY_LEDGERS};
 use crate::testutils::{
     self, assert_approx_eq_abs, assert_approx_eq_rel, est_close_mint, EnvTestUtils,
 };
-use crate::types::BootstrapConfig;
+use crate::types::{BootstrapConfig, ClaimDestination};
 use crate::BackstopBootstrapperClient;
 use blend_contract_sdk::testutils::BlendFixture;
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{vec, Address, BytesN, Env, Error, String};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
 
 #[test]
 fn test_claim_multiple_joiners() {
@@ -53,12 +53,21 @@ fn test_claim_multiple_joiners() {
     let bootstrap_amount = 100_000 * SCALAR_7;
     blnd_client.mint(&frodo, &bootstrap_amount);
     let config = BootstrapConfig {
-        pair_min: 2000 * SCALAR_7,
+        pair_min: vec![&e, 0, 2000 * SCALAR_7],
         close_ledger: e.ledger().sequence() + 3 * ONE_DAY_LEDGERS,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
     assert_eq!(bootstrap_amount, blnd_token.balance(&bootstrapper));
@@ -67,14 +76,14 @@ fn test_claim_multiple_joiners() {
     // join samwise 60% of total
     let join_amount_samwise = 1500 * SCALAR_7;
     usdc_client.mint(&samwise, &join_amount_samwise);
-    bootstrap_client.join(&samwise, &id, &join_amount_samwise);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount_samwise);
     assert_eq!(join_amount_samwise, usdc_token.balance(&bootstrapper));
     assert_eq!(0, usdc_token.balance(&samwise));
 
     // join pippin 30% of total
     let join_amount_pippin = 750 * SCALAR_7;
     usdc_client.mint(&pippin, &join_amount_pippin);
-    bootstrap_client.join(&pippin, &id, &join_amount_pippin);
+    bootstrap_client.join(&pippin, &id, &1, &join_amount_pippin);
     assert_eq!(
         join_amount_samwise + join_amount_pippin,
         usdc_token.balance(&bootstrapper)
@@ -84,7 +93,7 @@ fn test_claim_multiple_joiners() {
     // join merry 10% of total
     let join_amount_merry = 250 * SCALAR_7;
     usdc_client.mint(&merry, &join_amount_merry);
-    bootstrap_client.join(&merry, &id, &join_amount_merry);
+    bootstrap_client.join(&merry, &id, &1, &join_amount_merry);
     assert_eq!(
         join_amount_samwise + join_amount_pippin + join_amount_merry,
         usdc_token.balance(&bootstrapper)
@@ -100,7 +109,7 @@ fn test_claim_multiple_joiners() {
         comet_shares,
     );
     e.jump(3 * ONE_DAY_LEDGERS + 1);
-    let backstop_tokens = bootstrap_client.close(&id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
     assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_eq!(
@@ -212,12 +221,21 @@ fn test_claim_twice() {
     let bootstrap_amount = 100_000 * SCALAR_7;
     blnd_client.mint(&frodo, &bootstrap_amount);
     let config = BootstrapConfig {
-        pair_min: 2000 * SCALAR_7,
+        pair_min: vec![&e, 0, 2000 * SCALAR_7],
         close_ledger: e.ledger().sequence() + 3 * ONE_DAY_LEDGERS,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
     assert_eq!(bootstrap_amount, blnd_token.balance(&bootstrapper));
@@ -226,10 +244,10 @@ fn test_claim_twice() {
     // join samwise 60% of total
     let join_amount_samwise = 2000 * SCALAR_7;
     usdc_client.mint(&samwise, &join_amount_samwise);
-    bootstrap_client.join(&samwise, &id, &join_amount_samwise);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount_samwise);
 
     e.jump(3 * ONE_DAY_LEDGERS + 1);
-    let backstop_tokens = bootstrap_client.close(&id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
 
     // Mint bootstrapper backstop tokens so a double claim can be attempted
     usdc_client.mint(&bootstrapper, &(10000 * SCALAR_7));
@@ -240,9 +258,20 @@ fn test_claim_twice() {
         &bootstrapper,
     );
 
-    let est_frodo = backstop_tokens
-        .fixed_mul_floor(0_8000000, SCALAR_7)
-        .unwrap();
+    // pair side is undersubscribed, so the bootstrapper receives more than the pair side
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        backstop_tokens,
+        bootstrap.data.bootstrap_backstop_tokens
+            + bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    assert!(
+        bootstrap.data.bootstrap_backstop_tokens
+            > backstop_tokens
+                .fixed_mul_floor(0_8000000, SCALAR_7)
+                .unwrap()
+    );
+    let est_frodo = bootstrap.data.bootstrap_backstop_tokens;
     bootstrap_client.claim(&frodo, &id);
     assert_approx_eq_abs(
         est_frodo,
@@ -256,11 +285,7 @@ fn test_claim_twice() {
     let result = bootstrap_client.try_claim(&frodo, &id);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
 
-    let est_samwise = backstop_tokens
-        .fixed_mul_floor(0_2000000, SCALAR_7)
-        .unwrap()
-        .fixed_mul_floor(1_0000000, SCALAR_7)
-        .unwrap();
+    let est_samwise = bootstrap.data.pair_backstop_tokens.get_unchecked(1);
     bootstrap_client.claim(&samwise, &id);
     assert_approx_eq_abs(
         est_samwise,
@@ -273,3 +298,843 @@ fn test_claim_twice() {
     let result = bootstrap_client.try_claim(&samwise, &id);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
 }
+
+#[test]
+fn test_claim_all() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // frodo bootstraps the first and joins the second bootstrap
+    let bootstrap_amount = 10_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    blnd_client.mint(&merry, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id_0 = bootstrap_client.bootstrap(&config);
+    let mut config_merry = config.clone();
+    config_merry.bootstrapper = merry.clone();
+    let id_1 = bootstrap_client.bootstrap(&config_merry);
+
+    let join_amount = 250 * SCALAR_7;
+    usdc_client.mint(&samwise, &(2 * join_amount));
+    usdc_client.mint(&frodo, &join_amount);
+    bootstrap_client.join(&samwise, &id_0, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_1, &1, &join_amount);
+    bootstrap_client.join(&frodo, &id_1, &1, &join_amount);
+
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id_0);
+    bootstrap_client.close(&bombadil, &id_1);
+    let bootstrap_0 = bootstrap_client.get_bootstrap(&id_0);
+    let bootstrap_1 = bootstrap_client.get_bootstrap(&id_1);
+
+    // claim both bootstraps in one call
+    let ids = vec![&e, id_0, id_1];
+    let claimed = bootstrap_client.claim_all(&frodo, &ids);
+    let est_claim_1 = bootstrap_1
+        .data
+        .pair_backstop_tokens
+        .get_unchecked(1)
+        .fixed_mul_floor(0_5000000, SCALAR_7)
+        .unwrap();
+    assert_eq!(claimed.len(), 2);
+    assert_eq!(
+        claimed.get_unchecked(0),
+        bootstrap_0.data.bootstrap_backstop_tokens
+    );
+    assert_approx_eq_abs(claimed.get_unchecked(1), est_claim_1, MAX_DUST_AMOUNT);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "claim_all"), frodo.clone()).into_val(&e),
+                (ids.clone(), claimed.clone()).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(
+        claimed.get_unchecked(0) + claimed.get_unchecked(1),
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares
+    );
+
+    // verify bootstraps can't be claimed again
+    let result = bootstrap_client.try_claim(&frodo, &id_0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+    let result = bootstrap_client.try_claim_all(&frodo, &vec![&e, id_1]);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+}
+
+#[test]
+fn test_claim_to() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let pippin = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let other_pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "other"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 10_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 250 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    usdc_client.mint(&pippin, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+    bootstrap_client.join(&pippin, &id, &1, &join_amount);
+
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+
+    // claim to wallet
+    let claimed = bootstrap_client.claim_to(&frodo, &id, &ClaimDestination::Wallet);
+    assert_eq!(claimed, bootstrap.data.bootstrap_backstop_tokens);
+    assert_eq!(claimed, blend_fixture.backstop_token.balance(&frodo));
+    assert_eq!(
+        0,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares
+    );
+
+    // claim to a pool that was not deployed by the pool factory
+    let result = bootstrap_client.try_claim_to(
+        &samwise,
+        &id,
+        &ClaimDestination::OtherPool(Address::generate(&e)),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(103))));
+
+    // claim to a different pool's backstop
+    let est_claim = bootstrap
+        .data
+        .pair_backstop_tokens
+        .get_unchecked(1)
+        .fixed_mul_floor(0_5000000, SCALAR_7)
+        .unwrap();
+    let claimed = bootstrap_client.claim_to(
+        &samwise,
+        &id,
+        &ClaimDestination::OtherPool(other_pool_address.clone()),
+    );
+    assert_approx_eq_abs(claimed, est_claim, MAX_DUST_AMOUNT);
+    assert_eq!(
+        claimed,
+        blend_fixture
+            .backstop
+            .user_balance(&other_pool_address, &samwise)
+            .shares
+    );
+    assert_eq!(0, blend_fixture.backstop_token.balance(&samwise));
+
+    // claim to the bootstrap's pool backstop
+    let claimed = bootstrap_client.claim_to(&pippin, &id, &ClaimDestination::Backstop);
+    assert_approx_eq_abs(claimed, est_claim, MAX_DUST_AMOUNT);
+    assert_eq!(
+        claimed,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &pippin)
+            .shares
+    );
+
+    let result = bootstrap_client.try_claim_to(&pippin, &id, &ClaimDestination::Wallet);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+}
+
+#[test]
+fn test_claim_with_lock_ledgers() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let pippin = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 10_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let lock_ledgers = 10 * ONE_DAY_LEDGERS;
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 500 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    e.jump(ONE_DAY_LEDGERS);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    let samwise_tokens = bootstrap.data.pair_backstop_tokens.get_unchecked(1);
+    let frodo_tokens = bootstrap.data.bootstrap_backstop_tokens;
+
+    // verify release requires a claim first
+    let result = bootstrap_client.try_release(&samwise, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+    let result = bootstrap_client.try_release(&pippin, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+
+    // vesting claims can only be deposited into the pool's backstop
+    e.jump(2 * ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_claim_to(&samwise, &id, &ClaimDestination::Wallet);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(125))));
+    let result = bootstrap_client.try_claim_to(
+        &samwise,
+        &id,
+        &ClaimDestination::OtherPool(pool_address.clone()),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(125))));
+
+    // claim 2 days into the lock period releases 20%
+    let claimed = bootstrap_client.claim(&samwise, &id);
+    let released = samwise_tokens.fixed_mul_floor(0_2000000, SCALAR_7).unwrap();
+    assert_eq!(
+        claimed,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &samwise)
+            .shares
+    );
+    let vesting = bootstrap_client.get_vesting(&id, &samwise).unwrap();
+    assert_eq!(vesting.amount, samwise_tokens);
+    assert_eq!(vesting.released, released);
+    assert_eq!(vesting.start_ledger, config.close_ledger);
+    assert_eq!(vesting.cliff_ledger, config.close_ledger);
+    assert_eq!(vesting.end_ledger, config.close_ledger + lock_ledgers);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "claim"), samwise.clone(), id).into_val(&e),
+                (
+                    ClaimDestination::Backstop,
+                    samwise_tokens,
+                    released,
+                    claimed
+                )
+                    .into_val(&e)
+            )
+        ]
+    );
+
+    // the unreleased backstop tokens are held by the contract, not in the backstop
+    let frodo_claimed = bootstrap_client.claim(&frodo, &id);
+    assert_eq!(
+        frodo_claimed,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares
+    );
+    let frodo_released = frodo_tokens.fixed_mul_floor(0_2000000, SCALAR_7).unwrap();
+    assert_eq!(
+        blend_fixture.backstop_token.balance(&bootstrapper),
+        samwise_tokens + frodo_tokens - released - frodo_released
+    );
+    assert_eq!(
+        0,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &bootstrapper)
+            .shares
+    );
+
+    // nothing more to release in the same ledger
+    let minted = bootstrap_client.release(&samwise, &id);
+    assert_eq!(minted, 0);
+
+    // release halfway through the lock period
+    e.jump(3 * ONE_DAY_LEDGERS);
+    bootstrap_client.release(&samwise, &id);
+    let vesting = bootstrap_client.get_vesting(&id, &samwise).unwrap();
+    assert_eq!(
+        vesting.released,
+        samwise_tokens.fixed_mul_floor(0_5000000, SCALAR_7).unwrap()
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    let minted = blend_fixture
+        .backstop
+        .user_balance(&pool_address, &samwise)
+        .shares
+        - claimed;
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "release"), samwise.clone(), id).into_val(&e),
+                (vesting.released - released, minted).into_val(&e)
+            )
+        ]
+    );
+
+    // release the rest once the lock period is over
+    e.jump(10 * ONE_DAY_LEDGERS);
+    bootstrap_client.release(&samwise, &id);
+    bootstrap_client.release(&frodo, &id);
+    assert_approx_eq_abs(
+        samwise_tokens,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &samwise)
+            .shares,
+        10,
+    );
+    assert_approx_eq_abs(
+        frodo_tokens,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares,
+        10,
+    );
+    assert_eq!(0, blend_fixture.backstop_token.balance(&frodo));
+    assert_eq!(0, blend_fixture.backstop_token.balance(&bootstrapper));
+    e.as_contract(&bootstrapper, || {
+        let data = storage::get_bootstrap_data(&e, id);
+        assert_eq!(data.paid_backstop_tokens, samwise_tokens + frodo_tokens);
+    });
+
+    let minted = bootstrap_client.release(&samwise, &id);
+    assert_eq!(minted, 0);
+}
+
+#[test]
+fn test_claim_with_lock_ledgers_many_depositors() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 10_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 10 * ONE_DAY_LEDGERS,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    // more depositors than the backstop allows queued withdrawals for a single position
+    let join_amount = 20 * SCALAR_7;
+    let mut depositors = vec![&e];
+    for _ in 0..30 {
+        let depositor = Address::generate(&e);
+        usdc_client.mint(&depositor, &join_amount);
+        bootstrap_client.join(&depositor, &id, &1, &join_amount);
+        depositors.push_back(depositor);
+    }
+
+    e.jump(ONE_DAY_LEDGERS);
+    bootstrap_client.close(&bombadil, &id);
+
+    // every depositor can claim and release their locked backstop tokens
+    e.jump(2 * ONE_DAY_LEDGERS);
+    bootstrap_client.claim(&frodo, &id);
+    for depositor in depositors.iter() {
+        bootstrap_client.claim(&depositor, &id);
+    }
+    e.jump(4 * ONE_DAY_LEDGERS);
+    bootstrap_client.release(&frodo, &id);
+    for depositor in depositors.iter() {
+        bootstrap_client.release(&depositor, &id);
+    }
+    e.jump(5 * ONE_DAY_LEDGERS);
+    for depositor in depositors.iter() {
+        bootstrap_client.release(&depositor, &id);
+        let vesting = bootstrap_client.get_vesting(&id, &depositor).unwrap();
+        assert_eq!(vesting.released, vesting.amount);
+        assert_approx_eq_abs(
+            vesting.amount,
+            blend_fixture
+                .backstop
+                .user_balance(&pool_address, &depositor)
+                .shares,
+            10,
+        );
+    }
+    bootstrap_client.release(&frodo, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        blend_fixture.backstop_token.balance(&bootstrapper),
+        bootstrap.data.total_backstop_tokens - bootstrap.data.claimed_backstop_tokens
+    );
+}
+
+#[test]
+fn test_claim_bootstrapper_vesting() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 10_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 5 * ONE_DAY_LEDGERS,
+        bootstrapper_vesting_ledgers: 15 * ONE_DAY_LEDGERS,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 500 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    e.jump(ONE_DAY_LEDGERS);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    let frodo_tokens = bootstrap.data.bootstrap_backstop_tokens;
+
+    // depositors are not subject to the bootstrapper's vesting
+    e.jump(2 * ONE_DAY_LEDGERS);
+    let claimed = bootstrap_client.claim(&samwise, &id);
+    assert_eq!(
+        claimed,
+        bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    assert!(bootstrap_client.get_vesting(&id, &samwise).is_none());
+
+    // the bootstrapper's vesting claim can only be deposited into the pool's backstop
+    let result = bootstrap_client.try_claim_to(&frodo, &id, &ClaimDestination::Wallet);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(125))));
+    let result = bootstrap_client.try_claim_to(
+        &frodo,
+        &id,
+        &ClaimDestination::OtherPool(pool_address.clone()),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(125))));
+
+    // nothing is released before the cliff
+    let claimed = bootstrap_client.claim(&frodo, &id);
+    assert_eq!(claimed, 0);
+    let vesting = bootstrap_client.get_vesting(&id, &frodo).unwrap();
+    assert_eq!(vesting.amount, frodo_tokens);
+    assert_eq!(
+        vesting.cliff_ledger,
+        config.close_ledger + 5 * ONE_DAY_LEDGERS
+    );
+    assert_eq!(
+        vesting.end_ledger,
+        config.close_ledger + 15 * ONE_DAY_LEDGERS
+    );
+    let result = bootstrap_client.try_claim(&frodo, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+
+    e.jump(3 * ONE_DAY_LEDGERS - 1);
+    let minted = bootstrap_client.release(&frodo, &id);
+    assert_eq!(minted, 0);
+
+    // a third is released at the cliff
+    e.jump(1);
+    bootstrap_client.release(&frodo, &id);
+    assert_eq!(
+        bootstrap_client.get_vesting(&id, &frodo).unwrap().released,
+        frodo_tokens
+            .fixed_div_floor(3 * SCALAR_7, SCALAR_7)
+            .unwrap()
+    );
+
+    // the rest is released by the end of the vesting period
+    e.jump(10 * ONE_DAY_LEDGERS);
+    bootstrap_client.release(&frodo, &id);
+    assert_approx_eq_abs(
+        frodo_tokens,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares,
+        10,
+    );
+}
+
+#[test]
+fn test_claim_two_sided() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let pippin = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 40_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: true,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    // pippin joins with the bootstrap token, samwise with the pair token
+    // -> deposits are at the comet ratio (0.1 USDC per BLND), worth 6,250 USDC in total
+    let join_amount_pippin = 10_000 * SCALAR_7;
+    blnd_client.mint(&pippin, &join_amount_pippin);
+    bootstrap_client.join(&pippin, &id, &0, &join_amount_pippin);
+    let join_amount_samwise = 1_250 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount_samwise);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount_samwise);
+
+    // bootstrap token deposits do not count towards the pair token deposits
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        bootstrap.data.pair_deposits.get_unchecked(1),
+        join_amount_samwise
+    );
+    assert_eq!(bootstrap.data.bootstrap_amount, bootstrap_amount);
+    assert_eq!(
+        bootstrap.data.pair_amounts.get_unchecked(0),
+        join_amount_pippin
+    );
+
+    e.jump(ONE_DAY_LEDGERS);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    assert_approx_eq_rel(backstop_tokens, 5_000 * SCALAR_7, 0_0100000);
+
+    // proceeds are split by the value each user contributed
+    let claimed_frodo = bootstrap_client.claim_to(&frodo, &id, &ClaimDestination::Wallet);
+    let claimed_pippin = bootstrap_client.claim_to(&pippin, &id, &ClaimDestination::Wallet);
+    let claimed_samwise = bootstrap_client.claim_to(&samwise, &id, &ClaimDestination::Wallet);
+    assert_approx_eq_rel(claimed_frodo, 3_200 * SCALAR_7, 0_0100000);
+    assert_approx_eq_rel(claimed_pippin, 800 * SCALAR_7, 0_0100000);
+    assert_approx_eq_rel(claimed_samwise, 1_000 * SCALAR_7, 0_0100000);
+    assert_approx_eq_abs(claimed_frodo, claimed_pippin * 4, MAX_DUST_AMOUNT);
+    assert_approx_eq_abs(
+        claimed_frodo + claimed_pippin + claimed_samwise,
+        backstop_tokens,
+        10,
+    );
+}
+
+#[test]
+fn test_claim_backstop_token_deposit() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 10_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0_0500000,
+        keeper_window: 0,
+        keeper_fee: 0_0010000,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 500 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // merry joins with backstop tokens they already hold
+    let lp_amount = 100 * SCALAR_7;
+    blend_fixture
+        .backstop_token
+        .transfer(&bombadil, &merry, &lp_amount);
+    let deposited = bootstrap_client.join_lp(&merry, &id, &lp_amount);
+    assert_eq!(deposited, lp_amount);
+    let remaining = bootstrap_client.exit_lp(&merry, &id, &(20 * SCALAR_7));
+    assert_eq!(remaining, 80 * SCALAR_7);
+    let result = bootstrap_client.try_exit_lp(&merry, &id, &(80 * SCALAR_7 + 1));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(106))));
+    assert_eq!(blend_fixture.backstop_token.balance(&merry), 20 * SCALAR_7);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.data.total_backstop_tokens, 80 * SCALAR_7);
+    assert_eq!(bootstrap.data.backstop_token_deposits, 80 * SCALAR_7);
+
+    // backstop token deposits are not converted or charged the keeper fee on close
+    e.jump(ONE_DAY_LEDGERS);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        backstop_tokens,
+        bootstrap.data.bootstrap_backstop_tokens
+            + bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+            + 80 * SCALAR_7
+    );
+    assert_eq!(
+        blend_fixture.backstop_token.balance(&bootstrapper),
+        backstop_tokens
+    );
+
+    // merry's backstop tokens are deposited into the backstop on claim
+    let claimed = bootstrap_client.claim(&merry, &id);
+    assert_eq!(claimed, 80 * SCALAR_7);
+    assert_eq!(
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &merry)
+            .shares,
+        80 * SCALAR_7
+    );
+    let claimed = bootstrap_client.claim(&samwise, &id);
+    assert_eq!(
+        claimed,
+        bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+}
@@ -5,12 +5,13 @@ use crate::storage::ONE_DAY_LEDGERS;
 use crate::testutils::{
     self, assert_approx_eq_abs, assert_approx_eq_rel, est_close_mint, EnvTestUtils,
 };
-use crate::types::BootstrapConfig;
+use crate::types::{BootstrapConfig, BootstrapStatus};
 use crate::BackstopBootstrapperClient;
 use blend_contract_sdk::testutils::BlendFixture;
-use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, BytesN as _};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{Address, BytesN, Env, Error, String};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
 
 #[test]
 fn test_close_validates_status() {
@@ -44,29 +45,38 @@ fn test_close_validates_status() {
     blnd_client.mint(&frodo, &bootstrap_amount);
 
     let config = BootstrapConfig {
-        pair_min: 1 * SCALAR_7,
+        pair_min: vec![&e, 0, 1 * SCALAR_7],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
 
     let join_amount = 25 * SCALAR_7;
     usdc_client.mint(&samwise, &join_amount);
-    bootstrap_client.join(&samwise, &id, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
 
     // verify close cannot be run before close_ledger
     e.jump(ONE_DAY_LEDGERS - 1);
 
-    let result = bootstrap_client.try_close(&id);
+    let result = bootstrap_client.try_close(&bombadil, &id);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
 
     // verify close cannot be run once cancelled
     e.jump(14 * ONE_DAY_LEDGERS + 2);
 
-    let result = bootstrap_client.try_close(&id);
+    let result = bootstrap_client.try_close(&bombadil, &id);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
 }
 
@@ -117,19 +127,28 @@ fn test_close_blnd_bootstrap_single_sided_blnd() {
     blnd_client.mint(&frodo, &bootstrap_amount);
 
     let config = BootstrapConfig {
-        pair_min: 1 * SCALAR_7,
+        pair_min: vec![&e, 0, 1 * SCALAR_7],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
 
     // join bootstrap
     let join_amount = 1000 * SCALAR_7;
     usdc_client.mint(&samwise, &join_amount);
-    bootstrap_client.join(&samwise, &id, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
 
     // close bootstrap
     e.jump(ONE_DAY_LEDGERS + 1);
@@ -140,7 +159,7 @@ fn test_close_blnd_bootstrap_single_sided_blnd() {
         comet_usdc,
         comet_shares,
     );
-    let backstop_tokens = bootstrap_client.close(&id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
     assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_eq!(
@@ -197,19 +216,28 @@ fn test_close_usdc_bootstrap_single_sided_usdc() {
     let bootstrap_amount = 2000 * SCALAR_7;
     usdc_client.mint(&frodo, &bootstrap_amount);
     let config = BootstrapConfig {
-        pair_min: 1 * SCALAR_7,
+        pair_min: vec![&e, 1 * SCALAR_7, 0],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 1,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
 
     // join bootstrap
     let join_amount = 50000 * SCALAR_7;
     blnd_client.mint(&samwise, &join_amount);
-    bootstrap_client.join(&samwise, &id, &join_amount);
+    bootstrap_client.join(&samwise, &id, &0, &join_amount);
 
     // close bootstrap
     e.jump(ONE_DAY_LEDGERS + 1);
@@ -220,7 +248,7 @@ fn test_close_usdc_bootstrap_single_sided_usdc() {
         comet_usdc,
         comet_shares,
     );
-    let backstop_tokens = bootstrap_client.close(&id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
     assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_eq!(
@@ -230,3 +258,586 @@ fn test_close_usdc_bootstrap_single_sided_usdc() {
     // at most 3% slippage on close
     assert_approx_eq_rel(est_backstop_tokens, backstop_tokens, 0_0300000);
 }
+
+#[test]
+fn test_close_enforces_max_slippage() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // create two identical bootstraps with a large single sided blnd deposit
+    // blnd -> 600k
+    // usdc -> 1k
+    // approx 560k extra blnd (~27% of the comet blnd balance)
+    let bootstrap_amount = 600000 * SCALAR_7;
+    blnd_client.mint(&frodo, &(2 * bootstrap_amount));
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0_0100000,
+        max_price_deviation: 0_5000000,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id_strict = bootstrap_client.bootstrap(&config);
+    let mut config_loose = config.clone();
+    config_loose.max_slippage = 0_0500000;
+    let id_loose = bootstrap_client.bootstrap(&config_loose);
+
+    let join_amount = 1000 * SCALAR_7;
+    usdc_client.mint(&samwise, &(2 * join_amount));
+    bootstrap_client.join(&samwise, &id_strict, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_loose, &1, &join_amount);
+
+    e.jump(ONE_DAY_LEDGERS + 1);
+
+    // verify close is rejected when the deposit slips more than 1%
+    let result = bootstrap_client.try_close(&bombadil, &id_strict);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(114))));
+    assert_eq!(2 * bootstrap_amount, blnd_token.balance(&bootstrapper));
+
+    // verify close succeeds when up to 5% slippage is allowed
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id_loose);
+    assert!(backstop_tokens > 0);
+    assert_approx_eq_abs(
+        bootstrap_amount,
+        blnd_token.balance(&bootstrapper),
+        MAX_DUST_AMOUNT,
+    );
+}
+
+#[test]
+fn test_close_enforces_max_price_deviation() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // comet setup
+    // -> 2m BLND
+    // -> 50k USDC
+    // -> 0.1 USDC per BLND
+    let bootstrap_amount = 50000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0_0500000,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_approx_eq_rel(bootstrap.data.last_price, 0_1000000, 0_0010000);
+
+    let join_amount = 1000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    e.jump(ONE_DAY_LEDGERS / 2);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        bootstrap.data.price_cumulative,
+        bootstrap.data.last_price * (ONE_DAY_LEDGERS / 2) as i128
+    );
+
+    // push the comet spot price of blnd up by ~10% right before closing
+    e.jump(ONE_DAY_LEDGERS / 2 + 1);
+    let swap_amount = 2500 * SCALAR_7;
+    usdc_client.mint(&merry, &swap_amount);
+    let blnd_out = blend_fixture.backstop_token.swap_exact_amount_in(
+        &usdc,
+        &swap_amount,
+        &blnd,
+        &0,
+        &i128::MAX,
+        &merry,
+    );
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(116))));
+
+    // close succeeds once the price returns near its average
+    blend_fixture.backstop_token.swap_exact_amount_in(
+        &blnd,
+        &blnd_out.0,
+        &usdc,
+        &0,
+        &i128::MAX,
+        &merry,
+    );
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    assert!(backstop_tokens > 0);
+    assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+}
+
+#[test]
+fn test_close_price_deviation_follows_pokes() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 50000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0_0500000,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 1000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // the market moves the comet spot price of blnd up by ~10% and it stays there
+    e.jump(ONE_DAY_LEDGERS + 1);
+    let swap_amount = 2500 * SCALAR_7;
+    usdc_client.mint(&merry, &swap_amount);
+    blend_fixture.backstop_token.swap_exact_amount_in(
+        &usdc,
+        &swap_amount,
+        &blnd,
+        &0,
+        &i128::MAX,
+        &merry,
+    );
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(116))));
+
+    // without any recorded prices the average does not follow the market
+    e.jump(ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(116))));
+
+    // poke records the spot price clamped to within the deviation of the average, which is
+    // still the price recorded on join
+    let twap = bootstrap_client.get_bootstrap(&id).data.last_price;
+    assert!(bootstrap_client.poke(&id) == BootstrapStatus::Closing);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        bootstrap.data.last_price,
+        twap + twap.fixed_mul_floor(0_0500000, SCALAR_7).unwrap()
+    );
+    assert_eq!(bootstrap.data.last_price_ledger, e.ledger().sequence());
+
+    // the average follows the recorded price, so close succeeds
+    e.jump(ONE_DAY_LEDGERS);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    assert!(backstop_tokens > 0);
+    assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+}
+
+#[test]
+fn test_close_checks_price_deviation_on_every_close() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // comet setup
+    // -> 2m BLND
+    // -> 50k USDC
+    // -> 0.1 USDC per BLND
+    let bootstrap_amount = 1_200_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0_5000000,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    // swaps are limited to a third of the comet balance, so the price is pushed in steps
+    let swap_amount = 15000 * SCALAR_7;
+    usdc_client.mint(&merry, &(8 * swap_amount));
+    let skew = |swaps: u32| {
+        let mut blnd_out = 0;
+        for _ in 0..swaps {
+            blnd_out += blend_fixture
+                .backstop_token
+                .swap_exact_amount_in(&usdc, &swap_amount, &blnd, &0, &i128::MAX, &merry)
+                .0;
+        }
+        blnd_out
+    };
+    let restore = |blnd_out: i128| {
+        blend_fixture.backstop_token.swap_exact_amount_in(
+            &blnd,
+            &blnd_out,
+            &usdc,
+            &0,
+            &i128::MAX,
+            &merry,
+        );
+    };
+
+    // a join against a skewed comet only moves the recorded price by the max deviation
+    e.jump(ONE_DAY_LEDGERS / 2);
+    let blnd_out = skew(2);
+    bootstrap_client.join(&merry, &id, &1, &1);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_approx_eq_rel(bootstrap.data.last_price, 0_1500000, 0_0010000);
+    restore(blnd_out);
+
+    let join_amount = 1000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // the first close can't convert everything, and moves the comet spot price within the
+    // max deviation
+    e.jump(ONE_DAY_LEDGERS / 2 + 1);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Closing);
+
+    // later closes are checked as well
+    let blnd_out = skew(4);
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(116))));
+
+    restore(blnd_out);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+}
+
+#[test]
+fn test_close_keeper_window() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &(3 * bootstrap_amount));
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: ONE_DAY_LEDGERS,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id_keeper = bootstrap_client.bootstrap(&config);
+    let id_bootstrapper = bootstrap_client.bootstrap(&config);
+    let id_public = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &(3 * join_amount));
+    bootstrap_client.join(&samwise, &id_keeper, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_bootstrapper, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_public, &1, &join_amount);
+
+    // verify only the bootstrapper or a keeper can close during the keeper window
+    e.jump(ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_close(&samwise, &id_keeper);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+    let result = bootstrap_client.try_close(&merry, &id_keeper);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+
+    bootstrap_client.set_keeper(&merry, &true);
+    assert!(bootstrap_client.is_keeper(&merry));
+    bootstrap_client.close(&merry, &id_keeper);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            merry.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    bootstrapper.clone(),
+                    Symbol::new(&e, "close"),
+                    vec![&e, merry.into_val(&e), id_keeper.into_val(&e)],
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    bootstrap_client.close(&frodo, &id_bootstrapper);
+
+    // verify removed keepers can no longer close
+    bootstrap_client.set_keeper(&merry, &false);
+    assert!(!bootstrap_client.is_keeper(&merry));
+    let result = bootstrap_client.try_close(&merry, &id_public);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(4))));
+
+    // verify anyone can close once the keeper window has passed
+    e.jump(ONE_DAY_LEDGERS);
+    e.set_auths(&[]);
+    bootstrap_client.close(&samwise, &id_public);
+
+    let bootstrap = bootstrap_client.get_bootstrap(&id_keeper);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+    let bootstrap = bootstrap_client.get_bootstrap(&id_bootstrapper);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+    let bootstrap = bootstrap_client.get_bootstrap(&id_public);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+}
+
+#[test]
+fn test_close_pays_keeper_fee() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let comet_shares = blend_fixture.backstop_token.get_total_supply();
+    let comet_blnd = blnd_token.balance(&blend_fixture.backstop_token.address);
+    let comet_usdc = usdc_token.balance(&blend_fixture.backstop_token.address);
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 50000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let keeper_fee = 0_0050000;
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 1000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    e.jump(ONE_DAY_LEDGERS + 1);
+    let est_backstop_tokens = est_close_mint(
+        bootstrap_amount,
+        join_amount,
+        comet_blnd,
+        comet_usdc,
+        comet_shares,
+    );
+    let backstop_tokens = bootstrap_client.close(&merry, &id);
+
+    // verify the keeper fee is paid to the caller and excluded from the claim split
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    let keeper_fees = blend_fixture.backstop_token.balance(&merry);
+    assert!(keeper_fees > 0);
+    assert_eq!(bootstrap.data.keeper_fees, keeper_fees);
+    assert_approx_eq_rel(
+        keeper_fees,
+        (backstop_tokens + keeper_fees)
+            .fixed_mul_floor(keeper_fee, SCALAR_7)
+            .unwrap(),
+        0_0010000,
+    );
+    assert_eq!(
+        backstop_tokens,
+        blend_fixture.backstop_token.balance(&bootstrapper)
+    );
+    assert_eq!(
+        backstop_tokens,
+        bootstrap.data.bootstrap_backstop_tokens
+            + bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    assert_approx_eq_rel(
+        est_backstop_tokens,
+        backstop_tokens + keeper_fees,
+        0_0300000,
+    );
+}
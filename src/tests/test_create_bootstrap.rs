@@ -1,14 +1,16 @@
 #![cfg(test)]
 
-use crate::constants::SCALAR_7;
-use crate::storage::ONE_DAY_LEDGERS;
+use crate::constants::{
+    MAX_BOOTSTRAPPER_VESTING_DAYS, MAX_KEEPER_WINDOW_DAYS, MAX_LOCK_DAYS, SCALAR_7,
+};
+use crate::storage::{self, ONE_DAY_LEDGERS};
 use crate::testutils::{self, EnvTestUtils};
-use crate::types::BootstrapConfig;
+use crate::types::{BootstrapConfig, TokenInfo};
 use crate::BackstopBootstrapperClient;
 use blend_contract_sdk::testutils::BlendFixture;
-use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{Address, BytesN, Env, Error, String};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
 
 #[test]
 fn test_bootstrap_uses_next_id() {
@@ -44,12 +46,21 @@ fn test_bootstrap_uses_next_id() {
     let blnd_amount = 1000 * SCALAR_7;
     blnd_client.mint(&frodo, &blnd_amount);
     let config_1 = BootstrapConfig {
-        pair_min: 10 * SCALAR_7,
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS + 10,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: blnd_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id_1 = bootstrap_client.bootstrap(&config_1);
 
@@ -57,12 +68,21 @@ fn test_bootstrap_uses_next_id() {
     let usdc_amount = 10 * SCALAR_7;
     usdc_client.mint(&frodo, &usdc_amount);
     let config_2 = BootstrapConfig {
-        pair_min: 500 * SCALAR_7,
+        pair_min: vec![&e, 500 * SCALAR_7, 0],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS + 5,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: usdc_amount,
         token_index: 1,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id_2 = bootstrap_client.bootstrap(&config_2);
 
@@ -78,6 +98,8 @@ fn test_bootstrap_uses_next_id() {
     assert_eq!(config_1.pair_min, bootstrap_1.config.pair_min);
     assert_eq!(config_1.pool, bootstrap_1.config.pool);
     assert_eq!(config_1.token_index, bootstrap_1.config.token_index);
+    assert_eq!(0_8000000, bootstrap_1.data.weights.get_unchecked(0));
+    assert_eq!(0_2000000, bootstrap_1.data.weights.get_unchecked(1));
     let bootstrap_2 = bootstrap_client.get_bootstrap(&id_2);
     assert_eq!(id_2, bootstrap_2.id);
     assert_eq!(config_2.amount, bootstrap_2.config.amount);
@@ -86,6 +108,8 @@ fn test_bootstrap_uses_next_id() {
     assert_eq!(config_2.pair_min, bootstrap_2.config.pair_min);
     assert_eq!(config_2.pool, bootstrap_2.config.pool);
     assert_eq!(config_2.token_index, bootstrap_2.config.token_index);
+    assert_eq!(0_8000000, bootstrap_2.data.weights.get_unchecked(0));
+    assert_eq!(0_2000000, bootstrap_2.data.weights.get_unchecked(1));
 }
 
 #[test]
@@ -119,20 +143,105 @@ fn test_bootstrap_validates_config() {
     let blnd_amount = 1000 * SCALAR_7;
     blnd_client.mint(&frodo, &blnd_amount);
     let config = BootstrapConfig {
-        pair_min: 10 * SCALAR_7,
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS + 10,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: blnd_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
 
     // pair_min
     let mut config_pair_min = config.clone();
-    config_pair_min.pair_min = -1;
+    config_pair_min.pair_min = vec![&e, 0, -1];
     let result = bootstrap_client.try_bootstrap(&config_pair_min);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(8))));
 
+    config_pair_min.pair_min = vec![&e, 0];
+    let result = bootstrap_client.try_bootstrap(&config_pair_min);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(110))));
+
+    config_pair_min.pair_min = vec![&e, SCALAR_7, 10 * SCALAR_7];
+    let result = bootstrap_client.try_bootstrap(&config_pair_min);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(122))));
+
+    // pair_max
+    let mut config_pair_max = config.clone();
+    config_pair_max.pair_max = vec![&e, 0, 10 * SCALAR_7 - 1];
+    let result = bootstrap_client.try_bootstrap(&config_pair_max);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(111))));
+
+    config_pair_max.pair_max = vec![&e, 0, 0, 0];
+    let result = bootstrap_client.try_bootstrap(&config_pair_max);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(111))));
+
+    // max slippage
+    let mut config_slippage = config.clone();
+    config_slippage.max_slippage = -1;
+    let result = bootstrap_client.try_bootstrap(&config_slippage);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(113))));
+
+    config_slippage.max_slippage = SCALAR_7;
+    let result = bootstrap_client.try_bootstrap(&config_slippage);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(113))));
+
+    config_slippage.max_slippage = 0_0100000;
+    let result = bootstrap_client.try_bootstrap(&config_slippage);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(113))));
+
+    // max price deviation
+    let mut config_deviation = config.clone();
+    config_deviation.max_price_deviation = -1;
+    let result = bootstrap_client.try_bootstrap(&config_deviation);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(115))));
+
+    config_deviation.max_price_deviation = SCALAR_7;
+    let result = bootstrap_client.try_bootstrap(&config_deviation);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(115))));
+
+    // keeper window
+    let mut config_keeper = config.clone();
+    config_keeper.keeper_window = MAX_KEEPER_WINDOW_DAYS * ONE_DAY_LEDGERS + 1;
+    let result = bootstrap_client.try_bootstrap(&config_keeper);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(117))));
+
+    // keeper fee
+    let mut config_keeper_fee = config.clone();
+    config_keeper_fee.keeper_fee = -1;
+    let result = bootstrap_client.try_bootstrap(&config_keeper_fee);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(118))));
+
+    config_keeper_fee.keeper_fee = 0_0100001;
+    let result = bootstrap_client.try_bootstrap(&config_keeper_fee);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(118))));
+
+    // lock ledgers
+    let mut config_lock = config.clone();
+    config_lock.lock_ledgers = MAX_LOCK_DAYS * ONE_DAY_LEDGERS + 1;
+    let result = bootstrap_client.try_bootstrap(&config_lock);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(119))));
+
+    // bootstrapper vesting
+    let mut config_vesting = config.clone();
+    config_vesting.bootstrapper_vesting_ledgers =
+        MAX_BOOTSTRAPPER_VESTING_DAYS * ONE_DAY_LEDGERS + 1;
+    let result = bootstrap_client.try_bootstrap(&config_vesting);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(121))));
+
+    config_vesting.bootstrapper_vesting_ledgers = 30 * ONE_DAY_LEDGERS;
+    config_vesting.bootstrapper_cliff_ledgers = 30 * ONE_DAY_LEDGERS + 1;
+    let result = bootstrap_client.try_bootstrap(&config_vesting);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(121))));
+
     // close ledger
     let mut config_close_short = config.clone();
     config_close_short.close_ledger = e.ledger().sequence() + ONE_DAY_LEDGERS - 1;
@@ -165,3 +274,66 @@ fn test_bootstrap_validates_config() {
     let id = bootstrap_client.bootstrap(&config);
     assert_eq!(id, 0);
 }
+
+#[test]
+fn test_sync_token_data() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let token_data = bootstrap_client.get_token_data();
+    assert_eq!(token_data.len(), 2);
+    assert_eq!(token_data.get_unchecked(0).address, blnd);
+    assert_eq!(token_data.get_unchecked(0).weight, 0_8000000);
+    assert_eq!(token_data.get_unchecked(1).address, usdc);
+    assert_eq!(token_data.get_unchecked(1).weight, 0_2000000);
+
+    // no event is emitted if nothing changed
+    let num_events = e.events().all().len();
+    let synced = bootstrap_client.sync_token_data();
+    assert!(synced == token_data);
+    assert_eq!(num_events, e.events().all().len());
+
+    // simulate a reweight of the comet pool since the last sync
+    e.as_contract(&bootstrapper, || {
+        storage::set_comet_token_data(
+            &e,
+            &vec![
+                &e,
+                TokenInfo {
+                    address: blnd.clone(),
+                    weight: 0_5000000,
+                },
+                TokenInfo {
+                    address: usdc.clone(),
+                    weight: 0_5000000,
+                },
+            ],
+        );
+    });
+    let synced = bootstrap_client.sync_token_data();
+    assert!(synced == token_data);
+    assert!(bootstrap_client.get_token_data() == token_data);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "token_data"),).into_val(&e),
+                token_data.into_val(&e)
+            )
+        ]
+    );
+}
@@ -0,0 +1,254 @@
+#![cfg(test)]
+
+use crate::constants::{MAX_DUST_AMOUNT, SCALAR_7};
+use crate::storage::{self, ONE_DAY_LEDGERS};
+use crate::testutils::{self, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
+
+#[test]
+fn test_sweep_dust_after_close() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths_allowing_non_root_auth();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    usdc_client.mint(&samwise, &(25 * SCALAR_7));
+    usdc_client.mint(&merry, &(15 * SCALAR_7));
+    bootstrap_client.join(&samwise, &id, &1, &(25 * SCALAR_7));
+    bootstrap_client.join(&merry, &id, &1, &(15 * SCALAR_7));
+
+    // nothing can be swept while the bootstrap is active
+    let result = bootstrap_client.try_sweep_dust(&id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+    assert_eq!(bootstrap.data.depositors, 2);
+
+    // the unconverted tokens can be swept as soon as the bootstrap completes, but the backstop
+    // tokens are still owed
+    let blnd_dust = bootstrap.data.bootstrap_amount + bootstrap.data.pair_amounts.get(0).unwrap();
+    let usdc_dust = bootstrap.data.pair_amounts.get(1).unwrap();
+    assert!(blnd_dust <= MAX_DUST_AMOUNT);
+    let dust = bootstrap_client.get_dust(&id);
+    assert_eq!(dust.amounts, vec![&e, blnd_dust, usdc_dust]);
+    assert_eq!(dust.backstop_tokens, 0);
+
+    let blnd_balance = blnd_token.balance(&frodo);
+    let usdc_balance = usdc_token.balance(&frodo);
+    let swept = bootstrap_client.sweep_dust(&id);
+    assert_eq!(swept.amounts, dust.amounts);
+    assert_eq!(blnd_token.balance(&frodo), blnd_balance + blnd_dust);
+    assert_eq!(usdc_token.balance(&frodo), usdc_balance + usdc_dust);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "sweep_dust"), frodo.clone(), id).into_val(&e),
+                (vec![&e, blnd_dust, usdc_dust], 0i128).into_val(&e)
+            )
+        ]
+    );
+
+    // the swept tokens are no longer tracked, and the backstop tokens are still held
+    let dust = bootstrap_client.get_dust(&id);
+    assert_eq!(dust.amounts, vec![&e, 0, 0]);
+    let liabilities = bootstrap_client.get_liabilities();
+    assert_eq!(liabilities.len(), 3);
+    for liability in liabilities.iter() {
+        assert_eq!(liability.liabilities, liability.balance);
+    }
+    let backstop_liability = liabilities.get(2).unwrap();
+    assert_eq!(
+        backstop_liability.token,
+        blend_fixture.backstop_token.address
+    );
+    assert_eq!(
+        backstop_liability.liabilities,
+        bootstrap.data.total_backstop_tokens
+    );
+
+    // the backstop tokens left by rounding are swept once everyone has claimed
+    let mut claimed = bootstrap_client.claim(&frodo, &id);
+    claimed += bootstrap_client.claim(&samwise, &id);
+    assert_eq!(bootstrap_client.get_dust(&id).backstop_tokens, 0);
+    claimed += bootstrap_client.claim(&merry, &id);
+    let dust = bootstrap_client.get_dust(&id);
+    assert_eq!(
+        dust.backstop_tokens,
+        bootstrap.data.total_backstop_tokens - claimed
+    );
+    let swept = bootstrap_client.sweep_dust(&id);
+    assert_eq!(swept.backstop_tokens, dust.backstop_tokens);
+    assert_eq!(
+        blend_fixture.backstop_token.balance(&frodo),
+        dust.backstop_tokens
+    );
+    assert_eq!(blend_fixture.backstop_token.balance(&bootstrapper), 0);
+    e.as_contract(&bootstrapper, || {
+        let data = storage::get_bootstrap_data(&e, id);
+        assert_eq!(data.depositors, 0);
+        assert_eq!(data.claimed_backstop_tokens, data.total_backstop_tokens);
+        assert_eq!(data.paid_backstop_tokens, data.total_backstop_tokens);
+    });
+
+    // sweeping again sends nothing
+    let swept = bootstrap_client.sweep_dust(&id);
+    assert_eq!(swept.amounts, vec![&e, 0, 0]);
+    assert_eq!(swept.backstop_tokens, 0);
+    for liability in bootstrap_client.get_liabilities().iter() {
+        assert_eq!(liability.liabilities, 0);
+        assert_eq!(liability.balance, 0);
+    }
+}
+
+#[test]
+fn test_sweep_dust_after_cancel() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let merry = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 5 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    usdc_client.mint(&merry, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+    bootstrap_client.join(&merry, &id, &1, &join_amount);
+
+    // a depositor that fully exits no longer has a position
+    bootstrap_client.exit(&merry, &id, &1, &join_amount);
+    assert_eq!(bootstrap_client.get_bootstrap(&id).data.depositors, 1);
+
+    // the tokens held for the bootstrap are tracked as liabilities
+    let liabilities = bootstrap_client.get_liabilities();
+    assert_eq!(liabilities.get(0).unwrap().token, blnd);
+    assert_eq!(liabilities.get(0).unwrap().liabilities, bootstrap_amount);
+    assert_eq!(liabilities.get(1).unwrap().token, usdc);
+    assert_eq!(liabilities.get(1).unwrap().liabilities, join_amount);
+    for liability in liabilities.iter() {
+        assert_eq!(liability.liabilities, liability.balance);
+    }
+
+    // nothing is dust until everyone has been refunded
+    e.jump(ONE_DAY_LEDGERS + 1);
+    assert!(bootstrap_client.poke(&id) == BootstrapStatus::Cancelled);
+    bootstrap_client.refund(&frodo, &id);
+    let swept = bootstrap_client.sweep_dust(&id);
+    assert_eq!(swept.amounts, vec![&e, 0, 0]);
+    assert_eq!(swept.backstop_tokens, 0);
+    assert_eq!(
+        bootstrap_client
+            .get_liabilities()
+            .get(1)
+            .unwrap()
+            .liabilities,
+        join_amount
+    );
+
+    bootstrap_client.refund(&samwise, &id);
+    let dust = bootstrap_client.get_dust(&id);
+    assert_eq!(dust.amounts, vec![&e, 0, 0]);
+    assert_eq!(dust.backstop_tokens, 0);
+    for liability in bootstrap_client.get_liabilities().iter() {
+        assert_eq!(liability.liabilities, 0);
+        assert_eq!(liability.balance, 0);
+    }
+}
@@ -10,7 +10,7 @@ use crate::BackstopBootstrapperClient;
 use blend_contract_sdk::testutils::BlendFixture;
 use soroban_sdk::testutils::{Address as _, BytesN as _};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{Address, BytesN, Env, String};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, String};
 
 #[test]
 fn test_frontrunning_not_effective() {
@@ -59,19 +59,28 @@ fn test_frontrunning_not_effective() {
     blnd_client.mint(&frodo, &bootstrap_amount);
 
     let config = BootstrapConfig {
-        pair_min: 1 * SCALAR_7,
+        pair_min: vec![&e, 0, 1 * SCALAR_7],
         close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
 
     // join bootstrap
     let join_amount = 1000 * SCALAR_7;
     usdc_client.mint(&samwise, &join_amount);
-    bootstrap_client.join(&samwise, &id, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
 
     // close bootstrap
     e.jump(ONE_DAY_LEDGERS + 1);
@@ -108,7 +117,7 @@ fn test_frontrunning_not_effective() {
         &(333000 * SCALAR_7),
         &frodo,
     );
-    let backstop_tokens = bootstrap_client.close(&id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
     assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_eq!(
@@ -118,3 +127,84 @@ fn test_frontrunning_not_effective() {
     // at most 8% slippage on close
     assert_approx_eq_rel(est_backstop_tokens, backstop_tokens, 0_0800000);
 }
+
+#[test]
+fn test_frontrunning_rejected_with_guard() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // create bootstrap with the slippage and price deviation guards set
+    let bootstrap_amount = 50000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0_0500000,
+        max_price_deviation: 0_0500000,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    // join bootstrap
+    let join_amount = 1000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // frontrun close with the same swaps as above
+    e.jump(ONE_DAY_LEDGERS + 1);
+    usdc_client.mint(&frodo, &(50000 * &SCALAR_7));
+    for _ in 0..3 {
+        blend_fixture.backstop_token.swap_exact_amount_in(
+            &usdc,
+            &(16666 * &SCALAR_7),
+            &blnd,
+            &0,
+            &(333000 * SCALAR_7),
+            &frodo,
+        );
+    }
+
+    // verify the close is rejected and no tokens are deposited
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(116))));
+    assert_eq!(bootstrap_amount, blnd_token.balance(&bootstrapper));
+    assert_eq!(join_amount, usdc_token.balance(&bootstrapper));
+    assert_eq!(0, blend_fixture.backstop_token.balance(&bootstrapper));
+}
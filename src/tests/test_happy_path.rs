@@ -4,10 +4,9 @@ use crate::storage::ONE_DAY_LEDGERS;
 use crate::testutils::{
     self, assert_approx_eq_abs, assert_approx_eq_rel, est_close_mint, EnvTestUtils,
 };
-use crate::types::BootstrapConfig;
+use crate::types::{BootstrapConfig, BootstrapStatus, ClaimDestination};
 use crate::BackstopBootstrapperClient;
 use blend_contract_sdk::testutils::BlendFixture;
-use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::testutils::{Address as _, BytesN as _, Events, MockAuth, MockAuthInvoke};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
 use soroban_sdk::{vec, Address, BytesN, Env, IntoVal, String, Symbol, Val};
@@ -53,12 +52,21 @@ fn test_happy_path() {
     let pair_min = 10 * SCALAR_7;
     let duration = ONE_DAY_LEDGERS + 1;
     let config = BootstrapConfig {
-        pair_min,
+        pair_min: vec![&e, 0, pair_min],
         close_ledger: e.ledger().sequence() + duration,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     e.set_auths(&[]);
     let id = bootstrap_client
@@ -120,6 +128,7 @@ fn test_happy_path() {
                     &e,
                     samwise.into_val(&e),
                     id.into_val(&e),
+                    1u32.into_val(&e),
                     join_amount.into_val(&e),
                 ],
                 sub_invokes: &[MockAuthInvoke {
@@ -135,7 +144,7 @@ fn test_happy_path() {
                 }],
             },
         }])
-        .join(&samwise, &id, &join_amount);
+        .join(&samwise, &id, &1, &join_amount);
     assert_eq!(join_amount, usdc_token.balance(&bootstrapper));
     assert_eq!(0, usdc_token.balance(&samwise));
 
@@ -152,12 +161,13 @@ fn test_happy_path() {
                     &e,
                     samwise.into_val(&e),
                     id.into_val(&e),
+                    1u32.into_val(&e),
                     exit_amount.into_val(&e),
                 ],
                 sub_invokes: &[],
             },
         }])
-        .exit(&samwise, &id, &exit_amount);
+        .exit(&samwise, &id, &1, &exit_amount);
     assert_eq!(join_amount - exit_amount, usdc_token.balance(&bootstrapper));
     assert_eq!(exit_amount, usdc_token.balance(&samwise));
 
@@ -172,7 +182,7 @@ fn test_happy_path() {
     e.jump(duration + 1);
     e.set_auths(&[]);
     // no auths required by caller
-    let backstop_tokens = bootstrap_client.close(&id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
     let event = vec![&e, e.events().all().last_unchecked()];
     assert_eq!(
         event,
@@ -185,6 +195,19 @@ fn test_happy_path() {
             )
         ]
     );
+    let events = e.events().all();
+    let event = vec![&e, events.get_unchecked(events.len() - 2)];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "bootstrap_status"), id).into_val(&e),
+                BootstrapStatus::Completed.into_val(&e)
+            )
+        ]
+    );
     assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
     assert_eq!(
@@ -195,12 +218,16 @@ fn test_happy_path() {
     assert_approx_eq_rel(est_backstop_tokens, backstop_tokens, 0_0300000);
 
     // claim (backstop tokens are 1-1 with backstop shares)
-    let est_frodo = backstop_tokens
-        .fixed_mul_floor(0_8000000, SCALAR_7)
-        .unwrap();
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        backstop_tokens,
+        bootstrap.data.bootstrap_backstop_tokens
+            + bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    let est_frodo = bootstrap.data.bootstrap_backstop_tokens;
     e.set_auths(&[]);
 
-    bootstrap_client
+    let claimed_frodo = bootstrap_client
         .mock_auths(&[MockAuth {
             address: &frodo,
             invoke: &MockAuthInvoke {
@@ -231,6 +258,24 @@ fn test_happy_path() {
             },
         }])
         .claim(&frodo, &id);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "claim"), frodo.clone(), id).into_val(&e),
+                (
+                    ClaimDestination::Backstop,
+                    est_frodo,
+                    est_frodo,
+                    claimed_frodo
+                )
+                    .into_val(&e)
+            )
+        ]
+    );
     assert_approx_eq_abs(
         est_frodo,
         blend_fixture
@@ -240,9 +285,7 @@ fn test_happy_path() {
         MAX_DUST_AMOUNT,
     );
 
-    let est_samwise = backstop_tokens
-        .fixed_mul_floor(0_2000000, SCALAR_7)
-        .unwrap();
+    let est_samwise = bootstrap.data.pair_backstop_tokens.get_unchecked(1);
     e.set_auths(&[]);
     bootstrap_client
         .mock_auths(&[MockAuth {
@@ -333,12 +376,21 @@ fn test_happy_path_concurrent_bootstraps() {
     let pair_min = 10 * SCALAR_7;
     let duration = ONE_DAY_LEDGERS + 1;
     let mut config = BootstrapConfig {
-        pair_min,
+        pair_min: vec![&e, 0, pair_min],
         close_ledger: e.ledger().sequence() + duration,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     e.set_auths(&[]);
 
@@ -411,6 +463,7 @@ fn test_happy_path_concurrent_bootstraps() {
                     &e,
                     samwise.into_val(&e),
                     first_id.into_val(&e),
+                    1u32.into_val(&e),
                     join_amount.into_val(&e),
                 ],
                 sub_invokes: &[MockAuthInvoke {
@@ -426,7 +479,7 @@ fn test_happy_path_concurrent_bootstraps() {
                 }],
             },
         }])
-        .join(&samwise, &first_id, &join_amount);
+        .join(&samwise, &first_id, &1, &join_amount);
     assert_eq!(join_amount, usdc_token.balance(&bootstrapper));
     assert_eq!(join_amount, usdc_token.balance(&samwise));
 
@@ -441,6 +494,7 @@ fn test_happy_path_concurrent_bootstraps() {
                     &e,
                     samwise.into_val(&e),
                     second_id.into_val(&e),
+                    1u32.into_val(&e),
                     join_amount.into_val(&e),
                 ],
                 sub_invokes: &[MockAuthInvoke {
@@ -456,7 +510,7 @@ fn test_happy_path_concurrent_bootstraps() {
                 }],
             },
         }])
-        .join(&samwise, &second_id, &join_amount);
+        .join(&samwise, &second_id, &1, &join_amount);
     assert_eq!(join_amount * 2, usdc_token.balance(&bootstrapper));
     assert_eq!(0, usdc_token.balance(&samwise));
 
@@ -473,12 +527,13 @@ fn test_happy_path_concurrent_bootstraps() {
                     &e,
                     samwise.into_val(&e),
                     first_id.into_val(&e),
+                    1u32.into_val(&e),
                     exit_amount.into_val(&e),
                 ],
                 sub_invokes: &[],
             },
         }])
-        .exit(&samwise, &first_id, &exit_amount);
+        .exit(&samwise, &first_id, &1, &exit_amount);
     assert_eq!(
         join_amount * 2 - exit_amount,
         usdc_token.balance(&bootstrapper)
@@ -496,12 +551,13 @@ fn test_happy_path_concurrent_bootstraps() {
                     &e,
                     samwise.into_val(&e),
                     second_id.into_val(&e),
+                    1u32.into_val(&e),
                     exit_amount.into_val(&e),
                 ],
                 sub_invokes: &[],
             },
         }])
-        .exit(&samwise, &second_id, &exit_amount);
+        .exit(&samwise, &second_id, &1, &exit_amount);
     assert_eq!(
         join_amount * 2 - exit_amount * 2,
         usdc_token.balance(&bootstrapper)
@@ -519,7 +575,7 @@ fn test_happy_path_concurrent_bootstraps() {
     e.jump(duration + 1);
     e.set_auths(&[]);
     // no auths required by caller
-    let first_backstop_tokens = bootstrap_client.close(&first_id);
+    let first_backstop_tokens = bootstrap_client.close(&bombadil, &first_id);
     let event = vec![&e, e.events().all().last_unchecked()];
     assert_eq!(
         event,
@@ -561,7 +617,7 @@ fn test_happy_path_concurrent_bootstraps() {
     e.jump(100);
     e.set_auths(&[]);
     // no auths required by caller
-    let second_backstop_tokens = bootstrap_client.close(&second_id);
+    let second_backstop_tokens = bootstrap_client.close(&bombadil, &second_id);
     let event = vec![&e, e.events().all().last_unchecked()];
     assert_eq!(
         event,
@@ -584,9 +640,9 @@ fn test_happy_path_concurrent_bootstraps() {
     assert_approx_eq_rel(est_backstop_tokens, second_backstop_tokens, 0_0300000);
 
     // claim (backstop tokens are 1-1 with backstop shares)
-    let est_frodo = first_backstop_tokens
-        .fixed_mul_floor(0_8000000, SCALAR_7)
-        .unwrap();
+    let first_bootstrap = bootstrap_client.get_bootstrap(&first_id);
+    let second_bootstrap = bootstrap_client.get_bootstrap(&second_id);
+    let est_frodo = first_bootstrap.data.bootstrap_backstop_tokens;
     e.set_auths(&[]);
 
     bootstrap_client
@@ -629,9 +685,7 @@ fn test_happy_path_concurrent_bootstraps() {
         MAX_DUST_AMOUNT,
     );
 
-    let est_pippin = second_backstop_tokens
-        .fixed_mul_floor(0_8000000, SCALAR_7)
-        .unwrap();
+    let est_pippin = second_bootstrap.data.bootstrap_backstop_tokens;
     e.set_auths(&[]);
 
     bootstrap_client
@@ -674,9 +728,7 @@ fn test_happy_path_concurrent_bootstraps() {
         MAX_DUST_AMOUNT,
     );
 
-    let first_est_samwise = first_backstop_tokens
-        .fixed_mul_floor(0_2000000, SCALAR_7)
-        .unwrap();
+    let first_est_samwise = first_bootstrap.data.pair_backstop_tokens.get_unchecked(1);
     e.set_auths(&[]);
     bootstrap_client
         .mock_auths(&[MockAuth {
@@ -723,9 +775,7 @@ fn test_happy_path_concurrent_bootstraps() {
         MAX_DUST_AMOUNT,
     );
 
-    let second_est_samwise = second_backstop_tokens
-        .fixed_mul_floor(0_2000000, SCALAR_7)
-        .unwrap();
+    let second_est_samwise = second_bootstrap.data.pair_backstop_tokens.get_unchecked(1);
     e.set_auths(&[]);
     bootstrap_client
         .mock_auths(&[MockAuth {
@@ -3,12 +3,12 @@
 use crate::constants::SCALAR_7;
 use crate::storage::ONE_DAY_LEDGERS;
 use crate::testutils::{self, EnvTestUtils};
-use crate::types::BootstrapConfig;
+use crate::types::{BootstrapConfig, DepositData};
 use crate::BackstopBootstrapperClient;
 use blend_contract_sdk::testutils::BlendFixture;
-use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{Address, BytesN, Env, Error, String};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
 
 #[test]
 fn test_join_exit() {
@@ -50,12 +50,21 @@ fn test_join_exit() {
     let pair_min = 10 * SCALAR_7;
     let duration = 2 * ONE_DAY_LEDGERS;
     let config = BootstrapConfig {
-        pair_min,
+        pair_min: vec![&e, 0, pair_min],
         close_ledger: e.ledger().sequence() + duration,
         bootstrapper: frodo.clone(),
         pool: pool_address.clone(),
         amount: bootstrap_amount,
         token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
     };
     let id = bootstrap_client.bootstrap(&config);
     assert_eq!(bootstrap_amount, blnd_token.balance(&bootstrapper));
@@ -70,8 +79,22 @@ fn test_join_exit() {
     let join_amount = 100 * SCALAR_7;
     usdc_client.mint(&samwise, &init_bal_samwise);
     usdc_client.mint(&pippin, &init_bal_pippin);
-    bootstrap_client.join(&samwise, &id, &join_amount);
-    bootstrap_client.join(&pippin, &id, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+    bootstrap_client.join(&pippin, &id, &1, &join_amount);
+    let mut deposit = DepositData::new(&e);
+    deposit.add(1, join_amount);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "join"), pippin.clone(), id).into_val(&e),
+                (1u32, join_amount, deposit, join_amount * 2).into_val(&e)
+            )
+        ]
+    );
     assert_eq!(join_amount * 2, usdc_token.balance(&bootstrapper));
     assert_eq!(init_bal_samwise - join_amount, usdc_token.balance(&samwise));
     assert_eq!(init_bal_pippin - join_amount, usdc_token.balance(&pippin));
@@ -79,7 +102,21 @@ fn test_join_exit() {
     e.jump(duration / 2);
 
     let exit_amount = 75 * SCALAR_7;
-    bootstrap_client.exit(&samwise, &id, &exit_amount);
+    bootstrap_client.exit(&samwise, &id, &1, &exit_amount);
+    let mut deposit = DepositData::new(&e);
+    deposit.add(1, join_amount - exit_amount);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "exit"), samwise.clone(), id).into_val(&e),
+                (1u32, exit_amount, deposit, join_amount * 2 - exit_amount).into_val(&e)
+            )
+        ]
+    );
     assert_eq!(
         join_amount * 2 - exit_amount,
         usdc_token.balance(&bootstrapper)
@@ -89,19 +126,23 @@ fn test_join_exit() {
         usdc_token.balance(&samwise)
     );
 
-    let result = bootstrap_client.try_exit(&pippin, &id, &(join_amount + 1));
+    let result = bootstrap_client.try_exit(&pippin, &id, &1, &(join_amount + 1));
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(106))));
 
-    let result = bootstrap_client.try_exit(&samwise, &id, &(-1));
+    let result = bootstrap_client.try_exit(&samwise, &id, &1, &(-1));
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(8))));
 
-    let result = bootstrap_client.try_join(&pippin, &id, &(-1));
+    let result = bootstrap_client.try_join(&pippin, &id, &1, &(-1));
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(8))));
 
+    // the bootstrap token can only be joined with in two-sided bootstraps
+    let result = bootstrap_client.try_join(&pippin, &id, &0, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(122))));
+
     let join_2_amount = 15 * SCALAR_7;
     let exit_2_amount = 10 * SCALAR_7;
-    bootstrap_client.join(&samwise, &id, &join_2_amount);
-    bootstrap_client.exit(&pippin, &id, &exit_2_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_2_amount);
+    bootstrap_client.exit(&pippin, &id, &1, &exit_2_amount);
     let total_deposit = join_amount * 2 + join_2_amount - exit_amount - exit_2_amount;
     assert_eq!(total_deposit, usdc_token.balance(&bootstrapper));
     assert_eq!(
@@ -116,15 +157,162 @@ fn test_join_exit() {
     // verify bootstrapper data
     let bootstrap = bootstrap_client.get_bootstrap(&id);
     assert_eq!(bootstrap.data.bootstrap_amount, config.amount);
-    assert_eq!(bootstrap.data.total_pair, total_deposit);
-    assert_eq!(bootstrap.data.pair_amount, total_deposit);
+    assert_eq!(bootstrap.data.pair_deposits.get_unchecked(1), total_deposit);
+    assert_eq!(bootstrap.data.pair_amounts.get_unchecked(1), total_deposit);
 
     // move bootstrap out of active and try and join / exit
     e.jump(duration / 2);
 
-    let result = bootstrap_client.try_exit(&samwise, &id, &1);
+    let result = bootstrap_client.try_exit(&samwise, &id, &1, &1);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
 
-    let result = bootstrap_client.try_join(&pippin, &id, &1);
+    let result = bootstrap_client.try_join(&pippin, &id, &1, &1);
     assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
 }
+
+#[test]
+fn test_join_respects_pair_max() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let pippin = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let pair_max = 100 * SCALAR_7;
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + 2 * ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, pair_max],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    usdc_client.mint(&samwise, &(2 * pair_max));
+    usdc_client.mint(&pippin, &(2 * pair_max));
+    bootstrap_client.join(&samwise, &id, &1, &(60 * SCALAR_7));
+
+    // deposits beyond pair_max are rejected
+    let result = bootstrap_client.try_join(&pippin, &id, &1, &(40 * SCALAR_7 + 1));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(112))));
+
+    // deposits up to pair_max are accepted
+    bootstrap_client.join(&pippin, &id, &1, &(40 * SCALAR_7));
+    assert_eq!(pair_max, usdc_token.balance(&bootstrapper));
+
+    // capacity freed by an exit can be reused
+    bootstrap_client.exit(&samwise, &id, &1, &(10 * SCALAR_7));
+    let result = bootstrap_client.try_join(&pippin, &id, &1, &(10 * SCALAR_7 + 1));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(112))));
+    bootstrap_client.join(&pippin, &id, &1, &(10 * SCALAR_7));
+
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.data.pair_deposits.get_unchecked(1), pair_max);
+}
+
+#[test]
+fn test_join_rejects_bootstrapper() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &(2 * bootstrap_amount));
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: true,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    // the bootstrapper can't join with a pair token, the bootstrap token or backstop tokens
+    let join_amount = 10 * SCALAR_7;
+    usdc_client.mint(&frodo, &join_amount);
+    let result = bootstrap_client.try_join(&frodo, &id, &1, &join_amount);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(126))));
+    let result = bootstrap_client.try_join(&frodo, &id, &0, &join_amount);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(126))));
+
+    blend_fixture
+        .backstop_token
+        .transfer(&bombadil, &frodo, &join_amount);
+    let result = bootstrap_client.try_join_lp(&frodo, &id, &join_amount);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(126))));
+
+    // other users can still join
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.data.depositors, 1);
+    assert_eq!(bootstrap.data.backstop_token_deposits, 0);
+}
@@ -0,0 +1,128 @@
+#![cfg(test)]
+
+use crate::bootstrap::Bootstrap;
+use crate::constants::SCALAR_7;
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{self, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapFilter, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::token::StellarAssetClient;
+use soroban_sdk::{vec, Address, BytesN, Env, String, Vec};
+
+#[test]
+fn test_list_bootstraps() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_a = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "pool a"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let pool_b = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "pool b"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // create bootstraps
+    // -> 0: frodo, pool a, BLND
+    // -> 1: samwise, pool a, USDC
+    // -> 2: frodo, pool b, BLND (cancelled)
+    // -> 3: frodo, pool a, USDC
+    let bootstraps = [
+        (&frodo, &pool_a, 0),
+        (&samwise, &pool_a, 1),
+        (&frodo, &pool_b, 0),
+        (&frodo, &pool_a, 1),
+    ];
+    for (bootstrapper, pool, token_index) in bootstraps {
+        let amount = 100 * SCALAR_7;
+        blnd_client.mint(bootstrapper, &amount);
+        usdc_client.mint(bootstrapper, &amount);
+        let mut pair_min = vec![&e, SCALAR_7, SCALAR_7];
+        pair_min.set(token_index, 0);
+        bootstrap_client.bootstrap(&BootstrapConfig {
+            pair_min,
+            close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+            bootstrapper: bootstrapper.clone(),
+            pool: pool.clone(),
+            amount,
+            token_index,
+            pair_max: vec![&e, 0, 0],
+            max_slippage: 0,
+            max_price_deviation: 0,
+            keeper_window: 0,
+            keeper_fee: 0,
+            lock_ledgers: 0,
+            bootstrapper_cliff_ledgers: 0,
+            bootstrapper_vesting_ledgers: 0,
+            two_sided: false,
+        });
+    }
+    bootstrap_client.cancel(&2);
+
+    let ids = |bootstraps: Vec<Bootstrap>| {
+        let mut ids = vec![&e];
+        for bootstrap in bootstraps.iter() {
+            ids.push_back(bootstrap.id);
+        }
+        ids
+    };
+
+    // paginate through all bootstraps
+    let page = bootstrap_client.list_bootstraps(&0, &3, &vec![&e]);
+    assert_eq!(ids(page), vec![&e, 0, 1, 2]);
+    let page = bootstrap_client.list_bootstraps(&3, &3, &vec![&e]);
+    assert_eq!(ids(page), vec![&e, 3]);
+    let page = bootstrap_client.list_bootstraps(&4, &3, &vec![&e]);
+    assert_eq!(page.len(), 0);
+
+    // filter by pool, bootstrapper, token index, and status
+    let filters = vec![&e, BootstrapFilter::Pool(pool_a.clone())];
+    let page = bootstrap_client.list_bootstraps(&0, &10, &filters);
+    assert_eq!(ids(page), vec![&e, 0, 1, 3]);
+    let page = bootstrap_client.list_bootstraps(&1, &1, &filters);
+    assert_eq!(ids(page), vec![&e, 1]);
+
+    let filters = vec![&e, BootstrapFilter::Bootstrapper(frodo.clone())];
+    let page = bootstrap_client.list_bootstraps(&0, &10, &filters);
+    assert_eq!(ids(page), vec![&e, 0, 2, 3]);
+    let filters = vec![
+        &e,
+        BootstrapFilter::Pool(pool_a.clone()),
+        BootstrapFilter::Bootstrapper(frodo.clone()),
+        BootstrapFilter::TokenIndex(1),
+    ];
+    let page = bootstrap_client.list_bootstraps(&0, &10, &filters);
+    assert_eq!(ids(page), vec![&e, 3]);
+
+    let filters = vec![&e, BootstrapFilter::Status(BootstrapStatus::Active)];
+    let page = bootstrap_client.list_bootstraps(&0, &10, &filters);
+    assert_eq!(ids(page), vec![&e, 0, 1, 3]);
+    let filters = vec![&e, BootstrapFilter::Status(BootstrapStatus::Cancelled)];
+    let page = bootstrap_client.list_bootstraps(&0, &10, &filters);
+    assert_eq!(ids(page), vec![&e, 2]);
+}
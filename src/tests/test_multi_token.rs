@@ -0,0 +1,172 @@
+#![cfg(test)]
+
+use crate::constants::SCALAR_7;
+use crate::dependencies::comet;
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{assert_approx_eq_rel, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus, ClaimDestination, ProtocolParams};
+use crate::{BackstopBootstrapper, BackstopBootstrapperClient};
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, String};
+
+#[test]
+fn test_three_token_comet() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let pippin = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let xlm = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let xlm_client = StellarAssetClient::new(&e, &xlm);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+
+    // 3 token comet setup
+    // -> 1.2m BLND (0.1 USDC per BLND)
+    // -> 40k USDC
+    // -> 400k XLM (0.1 USDC per XLM)
+    // -> 200k shares (1 USDC per share)
+    let comet_address = e.register_contract_wasm(None, comet::WASM);
+    let comet_client = comet::Client::new(&e, &comet_address);
+    blnd_client.mint(&bombadil, &(600 * SCALAR_7 * 2001));
+    usdc_client.mint(&bombadil, &(20 * SCALAR_7 * 2001));
+    xlm_client.mint(&bombadil, &(200 * SCALAR_7 * 2001));
+    comet_client.init(
+        &bombadil,
+        &vec![&e, blnd.clone(), usdc.clone(), xlm.clone()],
+        &vec![&e, 0_6000000, 0_2000000, 0_2000000],
+        &vec![&e, 600 * SCALAR_7, 20 * SCALAR_7, 200 * SCALAR_7],
+        &0_0030000,
+    );
+    comet_client.join_pool(
+        &(199_900 * SCALAR_7),
+        &vec![
+            &e,
+            600 * SCALAR_7 * 2000,
+            20 * SCALAR_7 * 2000,
+            200 * SCALAR_7 * 2000,
+        ],
+        &bombadil,
+    );
+
+    let bootstrapper = e.register_contract(None, BackstopBootstrapper {});
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+    bootstrap_client.initialize(
+        &bombadil,
+        &blend_fixture.backstop.address,
+        &comet_address,
+        &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
+    );
+
+    // create bootstrap
+    let bootstrap_amount = 60000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 2000 * SCALAR_7, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.data.weights.len(), 3);
+    assert_eq!(bootstrap.data.pair_amounts.len(), 3);
+
+    // verify only the other comet tokens can be joined with
+    usdc_client.mint(&samwise, &(2000 * SCALAR_7));
+    xlm_client.mint(&pippin, &(20000 * SCALAR_7));
+    let result = bootstrap_client.try_join(&samwise, &id, &0, &SCALAR_7);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(122))));
+    let result = bootstrap_client.try_join(&samwise, &id, &3, &SCALAR_7);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(122))));
+
+    // join with both pair tokens
+    bootstrap_client.join(&samwise, &id, &1, &(2000 * SCALAR_7));
+    bootstrap_client.join(&pippin, &id, &2, &(20000 * SCALAR_7));
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(
+        bootstrap.data.pair_deposits,
+        vec![&e, 0, 2000 * SCALAR_7, 20000 * SCALAR_7]
+    );
+
+    // each pair token is capped by its own maximum
+    usdc_client.mint(&samwise, &SCALAR_7);
+    let result = bootstrap_client.try_join(&samwise, &id, &1, &SCALAR_7);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(112))));
+
+    // a bootstrap is cancelled if any pair token misses its minimum
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let short_id = bootstrap_client.bootstrap(&BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7, 100 * SCALAR_7],
+        pair_max: vec![&e, 0, 0, 0],
+        ..config.clone()
+    });
+    xlm_client.mint(&pippin, &(99 * SCALAR_7));
+    bootstrap_client.join(&samwise, &short_id, &1, &SCALAR_7);
+    bootstrap_client.join(&pippin, &short_id, &2, &(99 * SCALAR_7));
+
+    // close bootstrap
+    // -> deposits are at the comet ratio, worth 10k USDC in total
+    e.jump(ONE_DAY_LEDGERS + 1);
+    assert!(bootstrap_client.poke(&short_id) == BootstrapStatus::Cancelled);
+    bootstrap_client.refund(&frodo, &short_id);
+    bootstrap_client.refund(&samwise, &short_id);
+    bootstrap_client.refund(&pippin, &short_id);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    assert_approx_eq_rel(backstop_tokens, 10000 * SCALAR_7, 0_0100000);
+    assert_eq!(backstop_tokens, comet_client.balance(&bootstrapper));
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+    assert_eq!(TokenClient::new(&e, &usdc).balance(&bootstrapper), 0);
+    assert_eq!(TokenClient::new(&e, &xlm).balance(&bootstrapper), 0);
+
+    // claim proceeds, split by the value each token contributed
+    let claimed_frodo = bootstrap_client.claim_to(&frodo, &id, &ClaimDestination::Wallet);
+    let claimed_samwise = bootstrap_client.claim_to(&samwise, &id, &ClaimDestination::Wallet);
+    let claimed_pippin = bootstrap_client.claim_to(&pippin, &id, &ClaimDestination::Wallet);
+    assert_eq!(claimed_frodo, bootstrap.data.bootstrap_backstop_tokens);
+    assert_eq!(
+        claimed_samwise,
+        bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    assert_eq!(
+        claimed_pippin,
+        bootstrap.data.pair_backstop_tokens.get_unchecked(2)
+    );
+    assert_approx_eq_rel(claimed_frodo, 6000 * SCALAR_7, 0_0100000);
+    assert_approx_eq_rel(claimed_samwise, 2000 * SCALAR_7, 0_0100000);
+    assert_approx_eq_rel(claimed_pippin, 2000 * SCALAR_7, 0_0100000);
+    assert_eq!(comet_client.balance(&samwise), claimed_samwise);
+    assert_eq!(comet_client.balance(&pippin), claimed_pippin);
+}
@@ -0,0 +1,357 @@
+#![cfg(test)]
+
+use crate::constants::{MAX_DUST_AMOUNT, SCALAR_7};
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{self, assert_approx_eq_abs, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
+
+#[test]
+fn test_claim_pair_after_partial_close() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.set_default_info();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 1 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25000000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // partial close
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id);
+    let backstop_tokens = blend_fixture
+        .backstop_token
+        .balance(&bootstrap_client.address);
+    let usdc_balance = usdc_token.balance(&bootstrapper);
+    assert!(usdc_balance > MAX_DUST_AMOUNT);
+
+    // window for close expires
+    e.jump(14 * ONE_DAY_LEDGERS);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::PartiallyCompleted);
+    assert_eq!(
+        backstop_tokens,
+        bootstrap.data.bootstrap_backstop_tokens
+            + bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+
+    // the bootstrap can no longer be closed or refunded
+    let result = bootstrap_client.try_close(&bombadil, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let result = bootstrap_client.try_refund(&frodo, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let result = bootstrap_client.try_refund(&samwise, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    // claim bootstrapper
+    let claim_amount = bootstrap.data.bootstrap_backstop_tokens;
+    let claimed = bootstrap_client.claim(&frodo, &id);
+    assert_eq!(claim_amount, claimed);
+    assert_approx_eq_abs(
+        claim_amount,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares,
+        MAX_DUST_AMOUNT,
+    );
+    assert_approx_eq_abs(0, blnd_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+
+    // claim joiner, which also refunds the unconverted pair tokens
+    let refund_amount = bootstrap_client.quote_refund(&id, &samwise);
+    assert_approx_eq_abs(refund_amount, usdc_balance, MAX_DUST_AMOUNT);
+    let claim_amount = bootstrap.data.pair_backstop_tokens.get_unchecked(1);
+    let claimed = bootstrap_client.claim(&samwise, &id);
+    assert_eq!(claim_amount, claimed);
+    assert_approx_eq_abs(
+        claim_amount,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &samwise)
+            .shares,
+        MAX_DUST_AMOUNT,
+    );
+    assert_eq!(refund_amount, usdc_token.balance(&samwise));
+    assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+    let refund_event = (
+        bootstrapper.clone(),
+        (Symbol::new(&e, "refund"), samwise.clone(), id).into_val(&e),
+        (vec![&e, 0, refund_amount], 0i128).into_val(&e),
+    );
+    assert!(e.events().all().contains(refund_event));
+
+    // neither can claim again
+    let result = bootstrap_client.try_claim(&frodo, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+    let result = bootstrap_client.try_claim(&samwise, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+    assert_eq!(bootstrap_client.quote_refund(&id, &samwise), 0);
+}
+
+#[test]
+fn test_claim_pair_after_partial_close_multiple_joiners() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.set_default_info();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    let pippin = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 1 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount_samwise = 20000000 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount_samwise);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount_samwise);
+    let join_amount_pippin = 5000000 * SCALAR_7;
+    usdc_client.mint(&pippin, &join_amount_pippin);
+    bootstrap_client.join(&pippin, &id, &1, &join_amount_pippin);
+
+    let share_samwise = join_amount_samwise
+        .fixed_div_floor(join_amount_pippin + join_amount_samwise, SCALAR_7)
+        .unwrap();
+    let share_pippin = join_amount_pippin
+        .fixed_div_floor(join_amount_pippin + join_amount_samwise, SCALAR_7)
+        .unwrap();
+
+    // partial close
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    let claim_joiners = bootstrap.data.pair_backstop_tokens.get_unchecked(1);
+    let refund_joiners = usdc_token.balance(&bootstrapper);
+
+    // window for close expires, and the transition is stored by a poke
+    e.jump(14 * ONE_DAY_LEDGERS);
+    assert!(bootstrap_client.poke(&id) == BootstrapStatus::PartiallyCompleted);
+
+    // claim pippin
+    let claimed_pippin = bootstrap_client.claim(&pippin, &id);
+    assert_approx_eq_abs(
+        claim_joiners
+            .fixed_mul_floor(share_pippin, SCALAR_7)
+            .unwrap(),
+        claimed_pippin,
+        MAX_DUST_AMOUNT,
+    );
+    assert_approx_eq_abs(
+        refund_joiners
+            .fixed_mul_floor(share_pippin, SCALAR_7)
+            .unwrap(),
+        usdc_token.balance(&pippin),
+        MAX_DUST_AMOUNT,
+    );
+
+    // claim bootstrapper
+    let claimed = bootstrap_client.claim(&frodo, &id);
+    assert_eq!(bootstrap.data.bootstrap_backstop_tokens, claimed);
+
+    // claim samwise
+    let claimed_samwise = bootstrap_client.claim(&samwise, &id);
+    assert_approx_eq_abs(
+        claim_joiners
+            .fixed_mul_floor(share_samwise, SCALAR_7)
+            .unwrap(),
+        claimed_samwise,
+        MAX_DUST_AMOUNT,
+    );
+    assert_approx_eq_abs(
+        refund_joiners
+            .fixed_mul_floor(share_samwise, SCALAR_7)
+            .unwrap(),
+        usdc_token.balance(&samwise),
+        MAX_DUST_AMOUNT,
+    );
+
+    // only dust is left in the contract
+    assert_approx_eq_abs(0, usdc_token.balance(&bootstrapper), MAX_DUST_AMOUNT);
+    assert_approx_eq_abs(
+        0,
+        blend_fixture.backstop_token.balance(&bootstrapper),
+        MAX_DUST_AMOUNT,
+    );
+}
+
+#[test]
+fn test_claim_bootstrap_after_partial_close() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.set_default_info();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+    let usdc_token = TokenClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 10000000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 1 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // partial close
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id);
+    let blnd_balance = blnd_token.balance(&bootstrapper);
+    assert!(blnd_balance > MAX_DUST_AMOUNT);
+
+    // window for close expires
+    e.jump(14 * ONE_DAY_LEDGERS);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::PartiallyCompleted);
+    assert_eq!(bootstrap_client.quote_refund(&id, &frodo), blnd_balance);
+
+    // claim bootstrapper, which also refunds the unconverted bootstrap tokens
+    let claim_amount = bootstrap.data.bootstrap_backstop_tokens;
+    let claimed = bootstrap_client.claim(&frodo, &id);
+    assert_eq!(claim_amount, claimed);
+    assert_approx_eq_abs(
+        claim_amount,
+        blend_fixture
+            .backstop
+            .user_balance(&pool_address, &frodo)
+            .shares,
+        MAX_DUST_AMOUNT,
+    );
+    assert_eq!(blnd_balance, blnd_token.balance(&frodo));
+    assert_eq!(0, blnd_token.balance(&bootstrapper));
+
+    // claim joiner
+    let claim_amount = bootstrap.data.pair_backstop_tokens.get_unchecked(1);
+    let claimed = bootstrap_client.claim(&samwise, &id);
+    assert_eq!(claim_amount, claimed);
+    assert_approx_eq_abs(0, usdc_token.balance(&samwise), MAX_DUST_AMOUNT);
+
+    // refunds are not available for partially completed bootstraps
+    let result = bootstrap_client.try_refund(&frodo, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let result = bootstrap_client.try_refund(&samwise, &id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
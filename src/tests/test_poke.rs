@@ -0,0 +1,127 @@
+#![cfg(test)]
+
+use crate::constants::SCALAR_7;
+use crate::storage::{self, ONE_DAY_LEDGERS};
+use crate::testutils::{self, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
+use soroban_sdk::token::StellarAssetClient;
+use soroban_sdk::{vec, Address, BytesN, Env, IntoVal, String, Symbol};
+
+#[test]
+fn test_poke() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &(3 * bootstrap_amount));
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id_closing = bootstrap_client.bootstrap(&config);
+    let id_missed = bootstrap_client.bootstrap(&config);
+    let id_expired = bootstrap_client.bootstrap(&config);
+
+    // the first and last bootstraps meet pair_min, the second does not
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &(3 * join_amount));
+    bootstrap_client.join(&samwise, &id_closing, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_missed, &1, &SCALAR_7);
+    bootstrap_client.join(&samwise, &id_expired, &1, &join_amount);
+
+    // poking an active bootstrap keeps it active
+    assert!(bootstrap_client.poke(&id_closing) == BootstrapStatus::Active);
+
+    // views report the current status before it is stored
+    e.jump(ONE_DAY_LEDGERS);
+    let bootstrap = bootstrap_client.get_bootstrap(&id_closing);
+    assert!(bootstrap.data.status == BootstrapStatus::Closing);
+    e.as_contract(&bootstrapper, || {
+        let data = storage::get_bootstrap_data(&e, id_closing);
+        assert!(data.status == BootstrapStatus::Active);
+    });
+
+    // poke stores the transition and emits an event
+    assert!(bootstrap_client.poke(&id_closing) == BootstrapStatus::Closing);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "bootstrap_status"), id_closing).into_val(&e),
+                BootstrapStatus::Closing.into_val(&e)
+            )
+        ]
+    );
+    e.as_contract(&bootstrapper, || {
+        let data = storage::get_bootstrap_data(&e, id_closing);
+        assert!(data.status == BootstrapStatus::Closing);
+    });
+
+    // poking again does not emit another transition
+    let num_events = e.events().all().len();
+    assert!(bootstrap_client.poke(&id_closing) == BootstrapStatus::Closing);
+    assert_eq!(num_events, e.events().all().len());
+
+    // a bootstrap that missed pair_min is cancelled
+    assert!(bootstrap_client.poke(&id_missed) == BootstrapStatus::Cancelled);
+    e.as_contract(&bootstrapper, || {
+        let data = storage::get_bootstrap_data(&e, id_missed);
+        assert!(data.status == BootstrapStatus::Cancelled);
+    });
+
+    // a closed bootstrap stays completed
+    bootstrap_client.close(&bombadil, &id_closing);
+    e.as_contract(&bootstrapper, || {
+        let data = storage::get_bootstrap_data(&e, id_closing);
+        assert!(data.status == BootstrapStatus::Completed);
+    });
+
+    // a bootstrap that is never closed is cancelled once it expires
+    e.jump(14 * ONE_DAY_LEDGERS + 1);
+    assert!(bootstrap_client.poke(&id_closing) == BootstrapStatus::Completed);
+    assert!(bootstrap_client.poke(&id_expired) == BootstrapStatus::Cancelled);
+    let refunded = bootstrap_client.refund(&samwise, &id_expired);
+    assert_eq!(refunded, join_amount);
+}
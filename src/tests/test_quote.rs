@@ -0,0 +1,171 @@
+#![cfg(test)]
+
+use crate::constants::{MAX_DUST_AMOUNT, SCALAR_7};
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{self, assert_approx_eq_rel, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::token::StellarAssetClient;
+use soroban_sdk::{vec, Address, BytesN, Env, Error, String};
+
+#[test]
+fn test_quote_claim_and_close() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // create a bootstrap with more BLND than the comet ratio, so part is deposited single sided
+    let bootstrap_amount = 20_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0_0050000,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 250 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // refunds are quoted as if the bootstrap is cancelled
+    assert_eq!(bootstrap_client.quote_refund(&id, &frodo), bootstrap_amount);
+    assert_eq!(bootstrap_client.quote_refund(&id, &samwise), join_amount);
+
+    // quote the close and claims while the bootstrap is still active
+    let quote = bootstrap_client.simulate_close(&id);
+    assert_eq!(quote.closes, 1);
+    assert_eq!(quote.dust.len(), 2);
+    assert!(quote.dust.get_unchecked(0) <= MAX_DUST_AMOUNT);
+    assert_eq!(quote.dust.get_unchecked(1), 0);
+    let quote_frodo = bootstrap_client.quote_claim(&id, &frodo);
+    let quote_samwise = bootstrap_client.quote_claim(&id, &samwise);
+    assert_approx_eq_rel(
+        quote_frodo + quote_samwise,
+        quote.backstop_tokens,
+        0_0001000,
+    );
+
+    // the quotes match the close and claims
+    e.jump(ONE_DAY_LEDGERS + 1);
+    let backstop_tokens = bootstrap_client.close(&bombadil, &id);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Completed);
+    assert_approx_eq_rel(quote.backstop_tokens, backstop_tokens, 0_0010000);
+    assert_approx_eq_rel(quote.keeper_fees, bootstrap.data.keeper_fees, 0_0010000);
+    let result = bootstrap_client.try_simulate_close(&id);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    assert_eq!(
+        bootstrap_client.quote_claim(&id, &samwise),
+        bootstrap.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    let claimed_frodo = bootstrap_client.claim(&frodo, &id);
+    let claimed_samwise = bootstrap_client.claim(&samwise, &id);
+    assert_approx_eq_rel(quote_frodo, claimed_frodo, 0_0010000);
+    assert_approx_eq_rel(quote_samwise, claimed_samwise, 0_0010000);
+    assert_eq!(bootstrap_client.quote_claim(&id, &frodo), 0);
+    assert_eq!(bootstrap_client.quote_claim(&id, &samwise), 0);
+}
+
+#[test]
+fn test_simulate_close_multiple_closes() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    // create a bootstrap too large to deposit single sided in a single close
+    let bootstrap_amount = 1_000_000 * SCALAR_7;
+    blnd_client.mint(&frodo, &bootstrap_amount);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+    usdc_client.mint(&samwise, &SCALAR_7);
+    bootstrap_client.join(&samwise, &id, &1, &SCALAR_7);
+
+    e.jump(ONE_DAY_LEDGERS + 1);
+    let quote = bootstrap_client.simulate_close(&id);
+    assert_eq!(quote.closes, 2);
+
+    let mut closes = 0;
+    let mut backstop_tokens = 0;
+    while bootstrap_client.get_bootstrap(&id).data.status == BootstrapStatus::Closing {
+        backstop_tokens = bootstrap_client.close(&bombadil, &id);
+        closes += 1;
+    }
+    assert_eq!(closes, quote.closes);
+    assert_approx_eq_rel(quote.backstop_tokens, backstop_tokens, 0_0010000);
+}
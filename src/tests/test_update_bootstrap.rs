@@ -0,0 +1,149 @@
+#![cfg(test)]
+
+use crate::constants::SCALAR_7;
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{self, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, BytesN, Env, Error, IntoVal, String, Symbol};
+
+#[test]
+fn test_update_bootstrap() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let blnd_token = TokenClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let initial_balance = 2000 * SCALAR_7;
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &initial_balance);
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 100 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + 2 * ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id = bootstrap_client.bootstrap(&config);
+
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &join_amount);
+    bootstrap_client.join(&samwise, &id, &1, &join_amount);
+
+    // validate updates that harm depositors are rejected
+    let result = bootstrap_client.try_update_bootstrap(
+        &id,
+        &0,
+        &vec![&e, 0, 100 * SCALAR_7 + 1],
+        &config.close_ledger,
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(110))));
+
+    let result = bootstrap_client.try_update_bootstrap(
+        &id,
+        &0,
+        &config.pair_min,
+        &(config.close_ledger - 1),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(100))));
+
+    let result = bootstrap_client.try_update_bootstrap(
+        &id,
+        &0,
+        &config.pair_min,
+        &(e.ledger().sequence() + 14 * ONE_DAY_LEDGERS + 1),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(100))));
+
+    let result =
+        bootstrap_client.try_update_bootstrap(&id, &-1, &config.pair_min, &config.close_ledger);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(8))));
+
+    let result =
+        bootstrap_client.try_update_bootstrap(&id, &0, &vec![&e, 0, -1], &config.close_ledger);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(8))));
+
+    let result = bootstrap_client.try_update_bootstrap(&id, &0, &vec![&e, 0], &config.close_ledger);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(110))));
+
+    // top up, lower pair_min and extend the bootstrap
+    e.jump(ONE_DAY_LEDGERS);
+    let add_amount = 500 * SCALAR_7;
+    let new_pair_min = vec![&e, 0, 20 * SCALAR_7];
+    let new_close_ledger = config.close_ledger + 5 * ONE_DAY_LEDGERS;
+    bootstrap_client.update_bootstrap(&id, &add_amount, &new_pair_min, &new_close_ledger);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                bootstrapper.clone(),
+                (Symbol::new(&e, "bootstrap_update"), frodo.clone(), id).into_val(&e),
+                (add_amount, new_pair_min.clone(), new_close_ledger).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(
+        bootstrap_amount + add_amount,
+        blnd_token.balance(&bootstrapper)
+    );
+    assert_eq!(
+        initial_balance - bootstrap_amount - add_amount,
+        blnd_token.balance(&frodo)
+    );
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert_eq!(bootstrap.config.amount, bootstrap_amount + add_amount);
+    assert_eq!(bootstrap.config.pair_min, new_pair_min);
+    assert_eq!(bootstrap.config.close_ledger, new_close_ledger);
+    assert_eq!(
+        bootstrap.data.bootstrap_amount,
+        bootstrap_amount + add_amount
+    );
+    assert_eq!(bootstrap.data.pair_deposits.get_unchecked(1), join_amount);
+
+    // bootstrap remains active past the original close ledger
+    e.jump(2 * ONE_DAY_LEDGERS);
+    let bootstrap = bootstrap_client.get_bootstrap(&id);
+    assert!(bootstrap.data.status == BootstrapStatus::Active);
+
+    // bootstrap can't be updated once no longer active
+    e.jump(4 * ONE_DAY_LEDGERS);
+    let result = bootstrap_client.try_update_bootstrap(&id, &1, &new_pair_min, &new_close_ledger);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+use crate::constants::SCALAR_7;
+use crate::storage::ONE_DAY_LEDGERS;
+use crate::testutils::{self, EnvTestUtils};
+use crate::types::{BootstrapConfig, BootstrapStatus};
+use crate::BackstopBootstrapperClient;
+use blend_contract_sdk::testutils::BlendFixture;
+use soroban_sdk::testutils::{Address as _, BytesN as _};
+use soroban_sdk::token::StellarAssetClient;
+use soroban_sdk::{vec, Address, BytesN, Env, String};
+
+#[test]
+fn test_user_positions() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    e.set_default_info();
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+    let samwise = Address::generate(&e);
+
+    let blnd = e.register_stellar_asset_contract(bombadil.clone());
+    let usdc = e.register_stellar_asset_contract(bombadil.clone());
+    let blnd_client = StellarAssetClient::new(&e, &blnd);
+    let usdc_client = StellarAssetClient::new(&e, &usdc);
+
+    let blend_fixture = BlendFixture::deploy(&e, &bombadil, &blnd, &usdc);
+    let pool_address = blend_fixture.pool_factory.deploy(
+        &bombadil,
+        &String::from_str(&e, "test"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0,
+        &2,
+    );
+    let bootstrapper = testutils::create_bootstrapper(&e, &blend_fixture);
+    let bootstrap_client = BackstopBootstrapperClient::new(&e, &bootstrapper);
+
+    let bootstrap_amount = 1000 * SCALAR_7;
+    blnd_client.mint(&frodo, &(2 * bootstrap_amount));
+    let config = BootstrapConfig {
+        pair_min: vec![&e, 0, 10 * SCALAR_7],
+        close_ledger: e.ledger().sequence() + ONE_DAY_LEDGERS,
+        bootstrapper: frodo.clone(),
+        pool: pool_address.clone(),
+        amount: bootstrap_amount,
+        token_index: 0,
+        pair_max: vec![&e, 0, 0],
+        max_slippage: 0,
+        max_price_deviation: 0,
+        keeper_window: 0,
+        keeper_fee: 0,
+        lock_ledgers: 0,
+        bootstrapper_cliff_ledgers: 0,
+        bootstrapper_vesting_ledgers: 0,
+        two_sided: false,
+    };
+    let id_0 = bootstrap_client.bootstrap(&config);
+    let id_1 = bootstrap_client.bootstrap(&config);
+
+    // samwise joins both bootstraps, and joins the first one twice
+    let join_amount = 25 * SCALAR_7;
+    usdc_client.mint(&samwise, &(3 * join_amount));
+    bootstrap_client.join(&samwise, &id_0, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_1, &1, &join_amount);
+    bootstrap_client.join(&samwise, &id_0, &1, &join_amount);
+
+    let positions = bootstrap_client.get_user_positions(&samwise);
+    assert_eq!(positions.len(), 2);
+    let position_0 = positions.get_unchecked(0);
+    assert_eq!(position_0.id, id_0);
+    assert!(position_0.status == BootstrapStatus::Active);
+    assert_eq!(position_0.deposit.amount(1), 2 * join_amount);
+    assert_eq!(position_0.claimable, 0);
+    assert_eq!(position_0.refundable.len(), 0);
+    assert_eq!(positions.get_unchecked(1).id, id_1);
+    assert_eq!(bootstrap_client.get_user_positions(&frodo).len(), 0);
+
+    // cancel the second bootstrap and close the first
+    bootstrap_client.cancel(&id_1);
+    e.jump(ONE_DAY_LEDGERS + 1);
+    bootstrap_client.close(&bombadil, &id_0);
+
+    let bootstrap_0 = bootstrap_client.get_bootstrap(&id_0);
+    let positions = bootstrap_client.get_user_positions(&samwise);
+    let position_0 = positions.get_unchecked(0);
+    assert!(position_0.status == BootstrapStatus::Completed);
+    assert_eq!(
+        position_0.claimable,
+        bootstrap_0.data.pair_backstop_tokens.get_unchecked(1)
+    );
+    let position_1 = positions.get_unchecked(1);
+    assert!(position_1.status == BootstrapStatus::Cancelled);
+    assert_eq!(position_1.refundable, vec![&e, 0, join_amount]);
+
+    // positions are removed once claimed or refunded
+    let claimed = bootstrap_client.claim(&samwise, &id_0);
+    assert_eq!(claimed, position_0.claimable);
+    let positions = bootstrap_client.get_user_positions(&samwise);
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions.get_unchecked(0).id, id_1);
+
+    let refunded = bootstrap_client.refund(&samwise, &id_1);
+    assert_eq!(refunded, join_amount);
+    assert_eq!(bootstrap_client.get_user_positions(&samwise).len(), 0);
+}
@@ -1,12 +1,13 @@
 #![cfg(test)]
 
 use crate::{
-    constants::SCALAR_7, storage::ONE_DAY_LEDGERS, BackstopBootstrapper, BackstopBootstrapperClient,
+    constants::SCALAR_7, storage::ONE_DAY_LEDGERS, types::ProtocolParams, BackstopBootstrapper,
+    BackstopBootstrapperClient,
 };
 use blend_contract_sdk::testutils::BlendFixture;
 use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{
-    testutils::{Ledger as _, LedgerInfo},
+    testutils::{Address as _, Ledger as _, LedgerInfo},
     Address, Env,
 };
 
@@ -14,9 +15,11 @@ pub(crate) fn create_bootstrapper(e: &Env, blend_fixture: &BlendFixture) -> Addr
     let address = e.register_contract(None, BackstopBootstrapper {});
     let client = BackstopBootstrapperClient::new(e, &address);
     client.initialize(
+        &Address::generate(e),
         &blend_fixture.backstop.address,
         &blend_fixture.backstop_token.address,
         &blend_fixture.pool_factory.address,
+        &ProtocolParams::default(),
     );
     address
 }
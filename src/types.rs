@@ -1,4 +1,10 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env, Vec};
+
+use crate::{
+    constants::{MAX_DUST_AMOUNT, MAX_IN_RATIO, SCALAR_7},
+    storage::ONE_DAY_LEDGERS,
+};
 
 #[derive(Clone, Copy, PartialEq)]
 #[repr(u32)]
@@ -8,9 +14,45 @@ pub enum BootstrapStatus {
     Closing = 1,
     Completed = 2,
     Cancelled = 3,
+    PartiallyCompleted = 4,
 }
 
-#[derive(Clone)]
+/// The protocol parameters shared by all bootstraps, set by the admin
+#[derive(Clone, PartialEq)]
+#[contracttype]
+pub struct ProtocolParams {
+    /// The number of ledgers in a day, used to bound the keeper window, lock ledgers and
+    /// bootstrapper vesting of a bootstrap
+    pub one_day_ledgers: u32,
+    /// The minimum number of ledgers a bootstrap can be open for
+    pub min_duration: u32,
+    /// The maximum number of ledgers a bootstrap can be open for
+    pub max_duration: u32,
+    /// The number of ledgers after the close ledger a bootstrap can be closed before it expires
+    pub close_expiry: u32,
+    /// The maximum amount of a token held for a bootstrap that is considered dust
+    pub max_dust_amount: i128,
+    /// The maximum portion of a comet token's balance that can be deposited single sided
+    pub max_in_ratio: i128,
+    /// The portion of the maximum comet shares joined for on close, leaving room for rounding
+    pub join_buffer: i128,
+}
+
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        ProtocolParams {
+            one_day_ledgers: ONE_DAY_LEDGERS,
+            min_duration: ONE_DAY_LEDGERS,
+            max_duration: 14 * ONE_DAY_LEDGERS,
+            close_expiry: 14 * ONE_DAY_LEDGERS,
+            max_dust_amount: MAX_DUST_AMOUNT,
+            max_in_ratio: MAX_IN_RATIO,
+            join_buffer: SCALAR_7 - 0_0001000,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 #[contracttype]
 pub struct TokenInfo {
     pub address: Address,
@@ -26,8 +68,38 @@ pub struct BootstrapConfig {
     pub pool: Address,
     /// The amount of the bootstrap token to bootstrap
     pub amount: i128,
-    /// The minimum amount of the pair token to bootstrap
-    pub pair_min: i128,
+    /// The minimum amount of each pair token to bootstrap, indexed by comet token index. The
+    /// bootstrap is cancelled if any pair token is below its minimum. The bootstrap token's
+    /// entries in `pair_min` and `pair_max` must be zero unless the bootstrap is two-sided.
+    pub pair_min: Vec<i128>,
+    /// The maximum amount of each pair token that can be deposited, indexed by comet token
+    /// index. Zero for no maximum.
+    pub pair_max: Vec<i128>,
+    /// Whether depositors can also join with the bootstrap token. Their deposits are converted
+    /// alongside the bootstrapper's and share its backstop tokens by amount contributed.
+    pub two_sided: bool,
+    /// The maximum slippage allowed when depositing a single token into comet, relative to the
+    /// spot value of the deposited tokens (7 decimals). Zero to disable the check. Requires
+    /// `max_price_deviation` to be set.
+    pub max_slippage: i128,
+    /// The maximum deviation of the comet spot price from its time-weighted average allowed
+    /// when closing (7 decimals). Zero to disable the check.
+    pub max_price_deviation: i128,
+    /// The number of ledgers after `close_ledger` during which only the bootstrapper or a keeper
+    /// can close the bootstrap. Zero to allow anyone to close immediately.
+    pub keeper_window: u32,
+    /// The share of backstop tokens minted by each close paid to its caller (7 decimals)
+    pub keeper_fee: i128,
+    /// The number of ledgers after `close_ledger` over which claimed backstop tokens are
+    /// linearly released into the pool's backstop. The tokens are held by the contract until
+    /// released. Zero to release them immediately.
+    pub lock_ledgers: u32,
+    /// The number of ledgers after `close_ledger` before any of the bootstrapper's backstop
+    /// tokens are released
+    pub bootstrapper_cliff_ledgers: u32,
+    /// The number of ledgers after `close_ledger` over which the bootstrapper's backstop tokens
+    /// are linearly released into the pool's backstop. Zero to use `lock_ledgers` instead.
+    pub bootstrapper_vesting_ledgers: u32,
     /// The index of the comet underlying token being bootstrapped
     pub token_index: u32,
     /// The ledger number at which the bootstrap will close
@@ -37,29 +109,223 @@ pub struct BootstrapConfig {
 #[derive(Clone)]
 #[contracttype]
 pub struct BootstrapData {
-    /// The total number of pair tokens deposited for this bootstrap
-    pub total_pair: i128,
-    // The total of backstop tokens minted for this bootstrap
+    /// The status of the bootstrap, as of its last transition
+    pub status: BootstrapStatus,
+    /// The amount of each comet token deposited by depositors, indexed by comet token index.
+    /// Includes the bootstrap token for two-sided bootstraps.
+    pub pair_deposits: Vec<i128>,
+    // The total of backstop tokens minted for this bootstrap, less keeper fees
     pub total_backstop_tokens: i128,
+    /// The backstop tokens deposited directly by depositors, included in `total_backstop_tokens`
+    pub backstop_token_deposits: i128,
+    /// The backstop tokens minted by the bootstrap token, owed to the bootstrapper
+    pub bootstrap_backstop_tokens: i128,
+    /// The backstop tokens minted by each comet token, owed to its depositors, indexed by comet
+    /// token index
+    pub pair_backstop_tokens: Vec<i128>,
+    /// The backstop tokens paid to the callers of close
+    pub keeper_fees: i128,
     /// The amount of the boostrapped token held by the contract for this boostrap
     pub bootstrap_amount: i128,
-    /// The amount of pair tokens held by the contract for this bootstrap
-    pub pair_amount: i128,
+    /// The amount of each comet token held by the contract for this bootstrap's depositors,
+    /// indexed by comet token index
+    pub pair_amounts: Vec<i128>,
+    /// The normalized comet weight of each token, snapshotted at creation and each close
+    pub weights: Vec<i128>,
+    /// The number of ledgers after the close ledger the bootstrap can be closed before it
+    /// expires, snapshotted from the protocol parameters at creation
+    pub close_expiry: u32,
+    /// The maximum amount of a token held for the bootstrap that is considered dust,
+    /// snapshotted from the protocol parameters at creation
+    pub max_dust_amount: i128,
+    /// The time-weighted sum of the recorded comet spot prices of the bootstrap token
+    pub price_cumulative: i128,
+    /// The last recorded comet spot price of the bootstrap token, in pair tokens
+    pub last_price: i128,
+    /// The ledger the last comet spot price was recorded
+    pub last_price_ledger: u32,
+    /// The ledger the first comet spot price was recorded
+    pub price_start_ledger: u32,
+    /// The number of depositors with a position that has not been claimed or refunded
+    pub depositors: u32,
+    /// The backstop tokens owed to users that have claimed or been refunded, including any
+    /// still vesting
+    pub claimed_backstop_tokens: i128,
+    /// The backstop tokens sent out of the contract for this bootstrap
+    pub paid_backstop_tokens: i128,
+    /// The amount of each comet token sent out of the contract for this bootstrap by refunds and
+    /// sweeps, indexed by comet token index
+    pub paid_amounts: Vec<i128>,
+}
+
+impl BootstrapData {
+    /// Check if all tokens held for the bootstrap, other than dust, have been converted
+    pub fn is_converted(&self) -> bool {
+        self.bootstrap_amount <= self.max_dust_amount
+            && self.pair_amounts.iter().sum::<i128>() <= self.max_dust_amount
+    }
+}
+
+/// The expected outcome of closing a bootstrap against the current comet state
+#[derive(Clone)]
+#[contracttype]
+pub struct CloseQuote {
+    /// The backstop tokens expected to be minted for the bootstrap, less keeper fees
+    pub backstop_tokens: i128,
+    /// The backstop tokens expected to be paid to the callers of close
+    pub keeper_fees: i128,
+    /// The amount of each comet token expected to be left unconverted, indexed by comet token
+    /// index
+    pub dust: Vec<i128>,
+    /// The number of close calls expected to be required to complete the bootstrap
+    pub closes: u32,
+}
+
+/// A filter for listing bootstraps
+#[derive(Clone)]
+#[contracttype]
+pub enum BootstrapFilter {
+    /// Match bootstraps with the status
+    Status(BootstrapStatus),
+    /// Match bootstraps for the pool
+    Pool(Address),
+    /// Match bootstraps created by the bootstrapper
+    Bootstrapper(Address),
+    /// Match bootstraps of the comet token index
+    TokenIndex(u32),
+}
+
+/// The destination of the backstop tokens claimed from a bootstrap
+#[derive(Clone)]
+#[contracttype]
+pub enum ClaimDestination {
+    /// Deposit into the backstop of the bootstrap's pool
+    Backstop,
+    /// Transfer the backstop tokens to the claimer's wallet
+    Wallet,
+    /// Deposit into the backstop of a different pool
+    OtherPool(Address),
 }
 
 #[derive(Clone)]
 #[contracttype]
 pub struct DepositData {
-    pub amount: i128,
+    /// The amount of each pair token deposited, indexed by comet token index
+    pub amounts: Vec<i128>,
+    /// The amount of backstop tokens deposited directly
+    pub backstop_tokens: i128,
     pub claimed: bool,
     pub refunded: bool,
 }
-impl Default for DepositData {
-    fn default() -> Self {
+impl DepositData {
+    pub fn new(e: &Env) -> Self {
         DepositData {
-            amount: 0,
+            amounts: Vec::new(e),
+            backstop_tokens: 0,
             claimed: false,
             refunded: false,
         }
     }
+
+    /// Get the amount of a pair token deposited
+    ///
+    /// ### Arguments
+    /// * `index` - The comet token index of the pair token
+    pub fn amount(&self, index: u32) -> i128 {
+        self.amounts.get(index).unwrap_or(0)
+    }
+
+    /// Add to the amount of a pair token deposited
+    ///
+    /// ### Arguments
+    /// * `index` - The comet token index of the pair token
+    /// * `amount` - The amount to add
+    pub fn add(&mut self, index: u32, amount: i128) {
+        while self.amounts.len() <= index {
+            self.amounts.push_back(0);
+        }
+        self.amounts.set(index, self.amount(index) + amount);
+    }
+
+    /// Check if nothing is deposited
+    pub fn is_empty(&self) -> bool {
+        self.backstop_tokens == 0 && self.amounts.iter().all(|amount| amount == 0)
+    }
+}
+
+/// A user's deposit in a bootstrap and what they can currently claim or refund
+#[derive(Clone)]
+#[contracttype]
+pub struct UserPosition {
+    /// The id of the bootstrap
+    pub id: u32,
+    /// The status of the bootstrap
+    pub status: BootstrapStatus,
+    /// The deposit of the user
+    pub deposit: DepositData,
+    /// The backstop tokens the user can claim
+    pub claimable: i128,
+    /// The amount of each comet token the user can be refunded, indexed by comet token index.
+    /// Deposited backstop tokens are refunded as-is.
+    pub refundable: Vec<i128>,
+}
+
+/// Claimed backstop tokens held by the contract and deposited into the backstop for the claimer
+/// over time
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingData {
+    /// The total amount of backstop tokens vesting
+    pub amount: i128,
+    /// The amount of backstop tokens already released
+    pub released: i128,
+    /// The ledger vesting starts
+    pub start_ledger: u32,
+    /// The ledger before which no backstop tokens are released
+    pub cliff_ledger: u32,
+    /// The ledger vesting ends and all backstop tokens are released
+    pub end_ledger: u32,
+}
+
+impl VestingData {
+    /// Get the amount of backstop tokens vested at a ledger
+    ///
+    /// ### Arguments
+    /// * `ledger` - The ledger sequence
+    pub fn vested(&self, ledger: u32) -> i128 {
+        if ledger >= self.end_ledger {
+            self.amount
+        } else if ledger < self.cliff_ledger || ledger <= self.start_ledger {
+            0
+        } else {
+            self.amount
+                .fixed_mul_floor(
+                    (ledger - self.start_ledger) as i128,
+                    (self.end_ledger - self.start_ledger) as i128,
+                )
+                .unwrap_optimized()
+        }
+    }
+}
+
+/// The leftover tokens of a bootstrap that are owed to no one and can be swept
+#[derive(Clone)]
+#[contracttype]
+pub struct DustData {
+    /// The amount of each comet token, indexed by comet token index
+    pub amounts: Vec<i128>,
+    /// The amount of backstop tokens
+    pub backstop_tokens: i128,
+}
+
+/// The tokens the contract owes across all bootstraps, against what it holds
+#[derive(Clone)]
+#[contracttype]
+pub struct TokenLiability {
+    /// The address of the token
+    pub token: Address,
+    /// The amount of the token held for bootstraps, summed across all bootstraps
+    pub liabilities: i128,
+    /// The contract's balance of the token
+    pub balance: i128,
 }